@@ -0,0 +1,294 @@
+use google_sheets4::api::GridRange;
+use std::fmt;
+
+/// A range in A1 notation, e.g. `Sheet1!A1:C10`, `'My Sheet'!A:A`, `Sheet1!2:5` or `Sheet1`.
+///
+/// Column and row indices are zero-based; end indices are exclusive, matching `GridRange`.
+/// A missing bound means the range is unbounded on that side.
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct A1Range {
+    pub sheet: Option<String>,
+    pub start_col: Option<u32>,
+    pub start_row: Option<u32>,
+    pub end_col: Option<u32>,
+    pub end_row: Option<u32>,
+}
+
+impl A1Range {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Empty range".to_string());
+        }
+        let (sheet, cells) = split_sheet(s)?;
+        let mut range = A1Range { sheet, ..Default::default() };
+        let cells = match cells {
+            Some(c) => c,
+            None => return Ok(range),
+        };
+        let mut parts = cells.splitn(2, ':');
+        let (start_col, start_row) = parse_cell(parts.next().unwrap_or_default())?;
+        range.start_col = start_col;
+        range.start_row = start_row;
+        match parts.next() {
+            Some(end) => {
+                let (end_col, end_row) = parse_cell(end)?;
+                range.end_col = end_col.map(|c| c + 1);
+                range.end_row = end_row.map(|r| r + 1);
+                // `A:A` and `2:5` leave the other axis unbounded on both sides.
+                if start_row.is_none() && end_row.is_none() {
+                    range.start_row = None;
+                }
+                if start_col.is_none() && end_col.is_none() {
+                    range.start_col = None;
+                }
+                // Like Sheets, read `B5:A1` as `A1:B5`.
+                if let (Some(start), Some(end)) = (range.start_row, range.end_row) {
+                    if end <= start {
+                        (range.start_row, range.end_row) = (Some(end - 1), Some(start + 1));
+                    }
+                }
+                if let (Some(start), Some(end)) = (range.start_col, range.end_col) {
+                    if end <= start {
+                        (range.start_col, range.end_col) = (Some(end - 1), Some(start + 1));
+                    }
+                }
+            }
+            None => {
+                range.end_col = start_col.map(|c| c + 1);
+                range.end_row = start_row.map(|r| r + 1);
+            }
+        }
+        Ok(range)
+    }
+
+    /// True for a plain name with no cell bounds, which may be a sheet title or a named range.
+    pub fn is_bare_name(&self) -> bool {
+        self.sheet.is_some()
+            && self.start_col.is_none()
+            && self.start_row.is_none()
+            && self.end_col.is_none()
+            && self.end_row.is_none()
+    }
+
+    /// Number of rows and columns covered, if the range is bounded on both axes and not
+    /// inverted (which `parse` never produces, but a `GridRange` from the API might).
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        let rows = self.end_row?.checked_sub(self.start_row.unwrap_or(0))?;
+        let cols = self.end_col?.checked_sub(self.start_col.unwrap_or(0))?;
+        Some((rows, cols))
+    }
+
+    /// The single cell `rows` down and `cols` across from this range's top-left corner.
+    pub fn cell_at(&self, rows: u32, cols: u32) -> A1Range {
+        let col = self.start_col.unwrap_or(0) + cols;
+        let row = self.start_row.unwrap_or(0) + rows;
+        A1Range {
+            sheet: self.sheet.clone(),
+            start_col: Some(col),
+            start_row: Some(row),
+            end_col: Some(col + 1),
+            end_row: Some(row + 1),
+        }
+    }
+
+    /// Builds a `GridRange` for this range on the sheet with the given ID.
+    pub fn to_grid_range(&self, sheet_id: i32) -> GridRange {
+        GridRange {
+            sheet_id: Some(sheet_id),
+            start_column_index: self.start_col.map(|c| c as i32),
+            start_row_index: self.start_row.map(|r| r as i32),
+            end_column_index: self.end_col.map(|c| c as i32),
+            end_row_index: self.end_row.map(|r| r as i32),
+        }
+    }
+
+    /// Inverse of `to_grid_range`, given the title of the sheet the range lives on.
+    pub fn from_grid_range(grid: &GridRange, sheet_title: Option<&str>) -> Self {
+        A1Range {
+            sheet: sheet_title.map(|t| t.to_string()),
+            start_col: grid.start_column_index.map(|c| c as u32),
+            start_row: grid.start_row_index.map(|r| r as u32),
+            end_col: grid.end_column_index.map(|c| c as u32),
+            end_row: grid.end_row_index.map(|r| r as u32),
+        }
+    }
+}
+
+impl TryFrom<String> for A1Range {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        A1Range::parse(&s)
+    }
+}
+
+impl From<A1Range> for String {
+    fn from(r: A1Range) -> Self {
+        r.to_string()
+    }
+}
+
+impl fmt::Display for A1Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(sheet) = &self.sheet {
+            if sheet.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                write!(f, "{}", sheet)?;
+            } else {
+                write!(f, "'{}'", sheet.replace('\'', "''"))?;
+            }
+            if self.is_bare_name() {
+                return Ok(());
+            }
+            write!(f, "!")?;
+        }
+        let start_col = self.start_col.or(self.end_col.map(|_| 0));
+        let start_row = self.start_row.or(self.end_row.map(|_| 0));
+        write_cell(f, start_col, start_row)?;
+        let single = start_col.is_some()
+            && start_row.is_some()
+            && self.end_col == start_col.map(|c| c + 1)
+            && self.end_row == start_row.map(|r| r + 1);
+        if !single {
+            write!(f, ":")?;
+            write_cell(f, self.end_col.and_then(|c| c.checked_sub(1)), self.end_row.and_then(|r| r.checked_sub(1)))?;
+        }
+        Ok(())
+    }
+}
+
+/// The cells actually written when `values` is placed at the top-left of `grid`.
+pub fn written_extent(grid: &GridRange, values: &[Vec<String>]) -> GridRange {
+    let start_row = grid.start_row_index.unwrap_or(0);
+    let start_col = grid.start_column_index.unwrap_or(0);
+    let cols = values.iter().map(|row| row.len()).max().unwrap_or(0);
+    GridRange {
+        sheet_id: grid.sheet_id,
+        start_row_index: Some(start_row),
+        start_column_index: Some(start_col),
+        end_row_index: Some(start_row + values.len() as i32),
+        end_column_index: Some(start_col + cols as i32),
+    }
+}
+
+/// Whether two grid ranges share at least one cell. Missing bounds are unbounded;
+/// a missing sheet ID means sheet 0, as the API omits default values.
+pub fn grid_ranges_overlap(a: &GridRange, b: &GridRange) -> bool {
+    if a.sheet_id.unwrap_or(0) != b.sheet_id.unwrap_or(0) {
+        return false;
+    }
+    let axis = |a_start: Option<i32>, a_end: Option<i32>, b_start: Option<i32>, b_end: Option<i32>| {
+        a_start.unwrap_or(0) < b_end.unwrap_or(i32::MAX) && b_start.unwrap_or(0) < a_end.unwrap_or(i32::MAX)
+    };
+    axis(a.start_row_index, a.end_row_index, b.start_row_index, b.end_row_index)
+        && axis(a.start_column_index, a.end_column_index, b.start_column_index, b.end_column_index)
+}
+
+/// Whether `outer` covers every cell of `inner`.
+pub fn grid_range_contains(outer: &GridRange, inner: &GridRange) -> bool {
+    if outer.sheet_id.unwrap_or(0) != inner.sheet_id.unwrap_or(0) {
+        return false;
+    }
+    let axis = |o_start: Option<i32>, o_end: Option<i32>, i_start: Option<i32>, i_end: Option<i32>| {
+        o_start.unwrap_or(0) <= i_start.unwrap_or(0) && i_end.unwrap_or(i32::MAX) <= o_end.unwrap_or(i32::MAX)
+    };
+    axis(outer.start_row_index, outer.end_row_index, inner.start_row_index, inner.end_row_index)
+        && axis(outer.start_column_index, outer.end_column_index, inner.start_column_index, inner.end_column_index)
+}
+
+/// Converts a zero-based column index to letters (0 -> `A`, 26 -> `AA`).
+pub fn column_to_letters(mut col: u32) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (col % 26) as u8) as char);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Converts column letters to a zero-based column index (`A` -> 0, `AA` -> 26).
+pub fn letters_to_column(letters: &str) -> Result<u32, String> {
+    // Sheets tops out at column XFD, so anything longer is a sheet name, not a column.
+    if letters.is_empty() || letters.len() > 3 {
+        return Err(format!("Invalid column: {}", letters));
+    }
+    let mut col: u32 = 0;
+    for c in letters.chars() {
+        if !c.is_ascii_alphabetic() {
+            return Err(format!("Invalid column: {}", letters));
+        }
+        col = col * 26 + (c.to_ascii_uppercase() as u32 - 'A' as u32 + 1);
+    }
+    Ok(col - 1)
+}
+
+/// Formats a single zero-based cell address, e.g. `(2, 0)` -> `C1`.
+pub fn cell_address(col: u32, row: u32) -> String {
+    format!("{}{}", column_to_letters(col), row + 1)
+}
+
+fn write_cell(f: &mut fmt::Formatter<'_>, col: Option<u32>, row: Option<u32>) -> fmt::Result {
+    if let Some(c) = col {
+        write!(f, "{}", column_to_letters(c))?;
+    }
+    if let Some(r) = row {
+        write!(f, "{}", r + 1)?;
+    }
+    Ok(())
+}
+
+fn split_sheet(s: &str) -> Result<(Option<String>, Option<&str>), String> {
+    if let Some(rest) = s.strip_prefix('\'') {
+        // Quoted sheet name; `''` is an escaped quote.
+        let mut name = String::new();
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '\'' {
+                if let Some((_, '\'')) = chars.peek() {
+                    name.push('\'');
+                    chars.next();
+                    continue;
+                }
+                let after = &rest[i + 1..];
+                return match after.strip_prefix('!') {
+                    Some(cells) => Ok((Some(name), Some(cells))),
+                    None if after.is_empty() => Ok((Some(name), None)),
+                    None => Err(format!("Invalid range: {}", s)),
+                };
+            }
+            name.push(c);
+        }
+        return Err(format!("Unterminated sheet name: {}", s));
+    }
+    match s.rsplit_once('!') {
+        Some((sheet, cells)) => Ok((Some(sheet.to_string()), Some(cells))),
+        None if is_cell_ref(s) => Ok((None, Some(s))),
+        None => Ok((Some(s.to_string()), None)),
+    }
+}
+
+fn is_cell_ref(s: &str) -> bool {
+    s.split(':').all(|part| parse_cell(part).is_ok())
+}
+
+fn parse_cell(s: &str) -> Result<(Option<u32>, Option<u32>), String> {
+    let split = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+    let (letters, digits) = s.split_at(split);
+    if letters.is_empty() && digits.is_empty() {
+        return Err(format!("Invalid cell reference: {}", s));
+    }
+    let col = if letters.is_empty() { None } else { Some(letters_to_column(letters)?) };
+    let row = if digits.is_empty() {
+        None
+    } else {
+        let r: u32 = digits.parse().map_err(|_| format!("Invalid cell reference: {}", s))?;
+        if r == 0 {
+            return Err(format!("Invalid cell reference: {}", s));
+        }
+        Some(r - 1)
+    };
+    Ok((col, row))
+}
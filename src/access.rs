@@ -0,0 +1,66 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How far up the folder tree `GoogleApiClient::check_file_access` looks for an allowed
+/// folder before giving up.
+pub const MAX_FOLDER_DEPTH: usize = 20;
+
+/// How long a file found inside an allowed folder stays allowed before its parents are
+/// checked again, so moving it out of the folder takes effect without a restart.
+pub const GRANT_TTL: Duration = Duration::from_secs(300);
+
+/// Which Drive files and calendars the server may touch, from `AppConfig::access`.
+///
+/// Files are unrestricted until any spreadsheet, document or folder ID is configured;
+/// from then on a file must be listed itself or sit (at any depth) inside a listed folder.
+/// Calendars work the same way with their own list.
+#[derive(Debug, Default)]
+pub struct AccessPolicy {
+    file_ids: HashSet<String>, // spreadsheets and documents
+    folder_ids: HashSet<String>,
+    calendar_ids: HashSet<String>,
+    granted: Mutex<HashMap<String, Instant>>, // files found inside an allowed folder, and when
+}
+
+impl AccessPolicy {
+    pub fn from_config(config: &crate::config::AccessConfig) -> Self {
+        AccessPolicy {
+            file_ids: config.spreadsheet_ids.iter().chain(&config.document_ids).cloned().collect(),
+            folder_ids: config.folder_ids.iter().cloned().collect(),
+            calendar_ids: config.calendar_ids.iter().cloned().collect(),
+            granted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn restricts_files(&self) -> bool {
+        !self.file_ids.is_empty() || !self.folder_ids.is_empty()
+    }
+
+    /// Whether `id` is allowed without looking at its parents: it is listed, was found
+    /// inside an allowed folder within the last `GRANT_TTL`, or files are unrestricted.
+    pub fn file_listed(&self, id: &str) -> bool {
+        !self.restricts_files()
+            || self.file_ids.contains(id)
+            || self.folder_ids.contains(id)
+            || self.granted.lock().unwrap_or_else(|e| e.into_inner())
+                .get(id)
+                .is_some_and(|at| at.elapsed() < GRANT_TTL)
+    }
+
+    pub fn folder_allowed(&self, id: &str) -> bool {
+        self.folder_ids.contains(id)
+    }
+
+    /// Remembers for `GRANT_TTL` that `id` lies inside an allowed folder. Only grants are
+    /// cached, so a file moved into an allowed folder becomes reachable without a restart.
+    pub fn grant(&self, id: &str) {
+        let mut granted = self.granted.lock().unwrap_or_else(|e| e.into_inner());
+        granted.retain(|_, at| at.elapsed() < GRANT_TTL);
+        granted.insert(id.to_string(), Instant::now());
+    }
+
+    pub fn calendar_allowed(&self, id: &str) -> bool {
+        self.calendar_ids.is_empty() || self.calendar_ids.contains(id)
+    }
+}
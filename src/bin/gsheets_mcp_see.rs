@@ -1,4 +1,14 @@
-fn main() {
-    println!("gsheets_mcp_see entry point (stub)");
-    // TODO: Call SEE server main loop
+use std::sync::Arc;
+use gsheets_mcp::config::AppConfig;
+use gsheets_mcp::google_api_client::{AuthManager, GoogleApiClient};
+
+#[tokio::main]
+async fn main() {
+    let config = Arc::new(AppConfig::load(None).expect("Failed to load configuration"));
+    let mut client = GoogleApiClient::new(Arc::new(AuthManager)).await
+        .with_access_policy(gsheets_mcp::access::AccessPolicy::from_config(&config.access));
+    if let Some(path) = &config.journal.path {
+        client = client.with_journal(gsheets_mcp::journal::Journal::new(path));
+    }
+    gsheets_mcp::see_server::see_server_main(Arc::new(client), config).await;
 }
@@ -1,10 +1,114 @@
-#[derive(Default)]
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub auth: AuthConfig,
+    pub features: FeaturesConfig,
+    pub files: FilesConfig,
+    pub journal: JournalConfig,
+    pub tools: ToolsConfig,
+    pub access: AccessConfig,
+    pub sharing: SharingConfig,
 }
 
-#[derive(Default)]
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
 pub struct AuthConfig {
     pub method: String,
     pub service_account_key_path: Option<String>,
 }
+
+/// Opt-in tools that are only safe in trusted deployments.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct FeaturesConfig {
+    /// Enables `sheets_batch_update`, which forwards raw Sheets API requests.
+    pub raw_batch_update: bool,
+}
+
+/// Local filesystem access for tools that read files from disk.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct FilesConfig {
+    /// Directories `upload_spreadsheet` may read from; empty disables path-based uploads.
+    pub allowed_dirs: Vec<String>,
+}
+
+/// Undo journal for sheet writes.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    /// JSON Lines file recording the prior contents of every range written; unset disables
+    /// journaling and the `undo_operation` tool.
+    pub path: Option<String>,
+}
+
+/// Which tools are offered; see `crate::tools::ToolPolicy`.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    /// Hides every tool that writes to Google services.
+    pub read_only: bool,
+    /// Tool or family names (sheets, drive, docs, calendar, permissions); empty allows all.
+    pub allow: Vec<String>,
+    /// Tool or family names to hide; takes precedence over `allow`.
+    pub deny: Vec<String>,
+}
+
+/// Allowlist of Google resources; see `crate::access::AccessPolicy`. Empty lists leave
+/// that kind of resource unrestricted.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct AccessConfig {
+    pub spreadsheet_ids: Vec<String>,
+    pub document_ids: Vec<String>,
+    /// Drive folders whose contents, at any depth, are allowed.
+    pub folder_ids: Vec<String>,
+    pub calendar_ids: Vec<String>,
+}
+
+/// Guardrails for `add_permission`; see `crate::sharing::check_grant`.
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+pub struct SharingConfig {
+    /// Email domains that may be granted access; empty allows any domain.
+    pub allowed_domains: Vec<String>,
+    /// Highest role that may be granted (reader, commenter, writer, ...); defaults to writer.
+    pub max_role: Option<String>,
+    /// Permits `anyone` (link) sharing.
+    pub allow_anyone_sharing: bool,
+    /// Permits sharing with a whole Google Workspace domain.
+    pub allow_domain_sharing: bool,
+    /// JSON Lines file recording every permission change; unset logs them through tracing.
+    pub audit_log: Option<String>,
+}
+
+impl AppConfig {
+    /// Loads configuration from an optional TOML file, overridden by `GSHEETS_MCP__*`
+    /// environment variables (e.g. `GSHEETS_MCP__FEATURES__RAW_BATCH_UPDATE=true`).
+    /// List settings take comma-separated values (`GSHEETS_MCP__FILES__ALLOWED_DIRS=/data,/srv/in`).
+    pub fn load(path: Option<&str>) -> Result<Self, config::ConfigError> {
+        let config: Self = config::Config::builder()
+            .add_source(config::File::with_name(path.unwrap_or("gsheets_mcp")).required(false))
+            .add_source(
+                config::Environment::with_prefix("GSHEETS_MCP")
+                    .separator("__")
+                    .try_parsing(true)
+                    .list_separator(",")
+                    .with_list_parse_key("files.allowed_dirs")
+                    .with_list_parse_key("tools.allow")
+                    .with_list_parse_key("tools.deny")
+                    .with_list_parse_key("access.spreadsheet_ids")
+                    .with_list_parse_key("access.document_ids")
+                    .with_list_parse_key("access.folder_ids")
+                    .with_list_parse_key("access.calendar_ids")
+                    .with_list_parse_key("sharing.allowed_domains"),
+            )
+            .build()?
+            .try_deserialize()?;
+        crate::tools::validate_names(&config.tools.allow)
+            .and_then(|_| crate::tools::validate_names(&config.tools.deny))
+            .and_then(|_| crate::sharing::validate_max_role(&config.sharing))
+            .map_err(config::ConfigError::Message)?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,126 @@
+/// Parses delimiter-separated text following RFC 4180: fields may be wrapped in double
+/// quotes, a doubled quote inside a quoted field is a literal quote, and quoted fields
+/// may contain delimiters and line breaks.
+///
+/// Records end at `\n` or `\r\n` unless `record_separator` is given (the stdio commands
+/// use `;` or `|` to fit a grid on one line). A trailing separator does not start an
+/// empty record.
+pub fn parse_delimited(text: &str, delimiter: char, record_separator: Option<char>) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut field_started = false; // distinguishes an empty final field from no field
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let mut quote_line = 1;
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        let ends_record = match record_separator {
+            Some(sep) => c == sep,
+            None => c == '\n' || (c == '\r' && chars.peek() == Some(&'\n')),
+        };
+        if ends_record {
+            if c == '\r' {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+            field_started = false;
+            line += 1;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+            field_started = true;
+        } else if c == '"' && field.is_empty() {
+            in_quotes = true;
+            quote_line = line;
+            field_started = true;
+        } else {
+            field.push(c);
+            field_started = true;
+        }
+    }
+    if in_quotes {
+        return Err(format!("Unterminated quoted field starting on line {}", quote_line));
+    }
+    if field_started || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Splits `s` on `separator` outside double-quoted sections, keeping the quotes so the
+/// pieces can be parsed again with `parse_delimited`.
+pub fn split_unquoted(s: &str, separator: char) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            pieces.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    pieces.push(&s[start..]);
+    pieces
+}
+
+/// Guesses the delimiter from the first line: tab, semicolon or comma, whichever occurs
+/// most often outside quotes (comma on a tie).
+pub fn sniff_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or_default();
+    [',', '\t', ';']
+        .into_iter()
+        .max_by_key(|&d| (split_unquoted(first_line, d).len(), d == ','))
+        .unwrap_or(',')
+}
+
+/// Parses a delimiter option: a single character, or `\t` / `tab` for tab.
+pub fn parse_delimiter(s: &str) -> Result<char, String> {
+    match s {
+        "\\t" | "tab" => Ok('\t'),
+        _ => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != '"' && c != '\n' && c != '\r' => Ok(c),
+                _ => Err(format!("Delimiter must be a single character: {:?}", s)),
+            }
+        }
+    }
+}
+
+/// Writes rows as delimiter-separated text with CRLF line endings, quoting fields that
+/// contain the delimiter, a quote or a line break.
+pub fn write_delimited(rows: &[Vec<String>], delimiter: char) -> String {
+    let mut out = String::new();
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| {
+            if field.contains([delimiter, '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        }).collect();
+        out.push_str(&fields.join(&delimiter.to_string()));
+        out.push_str("\r\n");
+    }
+    out
+}
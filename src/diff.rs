@@ -0,0 +1,144 @@
+use crate::table::Table;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Deleted,
+    Modified,
+}
+
+/// One changed cell; `row` and `col` are offsets from the top-left of the compared ranges.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CellChange {
+    pub row: usize,
+    pub col: usize,
+    pub kind: ChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Compares two grids position by position. Blank and missing cells are equivalent.
+pub fn diff_cells(before: &[Vec<String>], after: &[Vec<String>]) -> Vec<CellChange> {
+    let cell = |grid: &[Vec<String>], r: usize, c: usize| {
+        grid.get(r).and_then(|row| row.get(c)).filter(|v| !v.is_empty()).cloned()
+    };
+    let rows = before.len().max(after.len());
+    let mut changes = Vec::new();
+    for r in 0..rows {
+        let cols = before.get(r).map_or(0, |row| row.len()).max(after.get(r).map_or(0, |row| row.len()));
+        for c in 0..cols {
+            let (old, new) = (cell(before, r, c), cell(after, r, c));
+            if let Some(kind) = classify(&old, &new) {
+                changes.push(CellChange { row: r, col: c, kind, before: old, after: new });
+            }
+        }
+    }
+    changes
+}
+
+/// The changes made by writing `values` over `current` at the same top-left corner.
+/// Unlike `diff_cells`, cells outside the written block are left alone.
+pub fn write_changes(current: &[Vec<String>], values: &[Vec<String>]) -> Vec<CellChange> {
+    let mut changes = Vec::new();
+    for (r, row) in values.iter().enumerate() {
+        for (c, value) in row.iter().enumerate() {
+            let old = current.get(r).and_then(|row| row.get(c)).filter(|v| !v.is_empty()).cloned();
+            let new = Some(value.clone()).filter(|v| !v.is_empty());
+            if let Some(kind) = classify(&old, &new) {
+                changes.push(CellChange { row: r, col: c, kind, before: old, after: new });
+            }
+        }
+    }
+    changes
+}
+
+fn classify(old: &Option<String>, new: &Option<String>) -> Option<ChangeKind> {
+    match (old, new) {
+        (None, None) => None,
+        (None, Some(_)) => Some(ChangeKind::Added),
+        (Some(_), None) => Some(ChangeKind::Deleted),
+        (Some(a), Some(b)) if a == b => None,
+        (Some(_), Some(_)) => Some(ChangeKind::Modified),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RowChange {
+    pub key: String,
+    pub columns: Vec<String>, // headers of the changed columns
+}
+
+/// Row-level view of a diff. Rows are identified by the key column when one is given,
+/// otherwise by their 1-based position below the header.
+#[derive(Debug, Default, PartialEq, serde::Serialize)]
+pub struct RowSummary {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<RowChange>,
+    pub unchanged: usize,
+}
+
+pub fn summarize_rows(before: &Table, after: &Table, key_column: Option<&str>) -> Result<RowSummary, String> {
+    let keyed = |table: &Table| -> Result<Vec<(String, Vec<String>)>, String> {
+        let key_col = match key_column {
+            Some(name) => Some(table.column_index(name).ok_or_else(|| format!("Unknown key column: {}", name))?),
+            None => None,
+        };
+        Ok(table.rows.iter().enumerate().filter(|(_, row)| !crate::table::is_blank(row)).map(|(i, row)| {
+            let key = key_col.map_or_else(|| (i + 1).to_string(), |c| row[c].trim().to_string());
+            (key, row.clone())
+        }).collect())
+    };
+    let old_rows = keyed(before)?;
+    let new_rows = keyed(after)?;
+    let old_index: std::collections::HashMap<&str, &Vec<String>> = old_rows.iter().map(|(k, r)| (k.as_str(), r)).collect();
+    let new_keys: std::collections::HashSet<&str> = new_rows.iter().map(|(k, _)| k.as_str()).collect();
+
+    let mut summary = RowSummary::default();
+    for (key, row) in &new_rows {
+        let Some(old) = old_index.get(key.as_str()) else {
+            summary.added.push(key.clone());
+            continue;
+        };
+        // Compare by header so that reordered columns are not reported as changes.
+        let mut columns: Vec<String> = after.headers.iter().enumerate()
+            .filter(|(c, header)| {
+                let old_value = before.column_index(header).and_then(|oc| old.get(oc)).map_or("", |v| v.as_str());
+                old_value != row[*c]
+            })
+            .map(|(_, header)| header.clone())
+            .collect();
+        // Values in columns that no longer exist count as changed too.
+        columns.extend(before.headers.iter().enumerate()
+            .filter(|(c, header)| after.column_index(header).is_none() && !old[*c].is_empty())
+            .map(|(_, header)| header.clone()));
+        if columns.is_empty() {
+            summary.unchanged += 1;
+        } else {
+            summary.modified.push(RowChange { key: key.clone(), columns });
+        }
+    }
+    summary.removed = old_rows.iter().map(|(k, _)| k).filter(|k| !new_keys.contains(k.as_str())).cloned().collect();
+    Ok(summary)
+}
+
+/// Hex FNV-1a hash of a grid's contents. Trailing blank cells and rows are ignored, since
+/// the Sheets API trims them from responses, so equal content always hashes the same.
+pub fn content_hash(values: &[Vec<String>]) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let trimmed = |row: &Vec<String>| row.iter().rposition(|v| !v.is_empty()).map_or(0, |i| i + 1);
+    let rows = values.iter().rposition(|row| trimmed(row) > 0).map_or(0, |i| i + 1);
+    let mut hash = OFFSET;
+    for row in &values[..rows] {
+        for cell in &row[..trimmed(row)] {
+            // 0x1f and 0x1e (unit and record separators) keep ["ab"] and ["a", "b"] apart.
+            for byte in cell.bytes().chain([0x1f]) {
+                hash = (hash ^ byte as u64).wrapping_mul(PRIME);
+            }
+        }
+        hash = (hash ^ 0x1e).wrapping_mul(PRIME);
+    }
+    format!("{:016x}", hash)
+}
@@ -1,5 +1,8 @@
 use google_drive3::api::{DriveHub, Permission};
-use google_sheets4::api::Sheets;
+use google_sheets4::api::{
+    BatchUpdateSpreadsheetRequest, BatchUpdateSpreadsheetResponse, ConditionalFormatRule, GridRange, NamedRange, ProtectedRange, Request, SheetProperties,
+    Sheets, Spreadsheet, ValueRange,
+};
 use google_docs1::api::Docs;
 use google_calendar3::api::CalendarHub;
 use yup_oauth2::{ServiceAccountAuthenticator, ServiceAccountKey, read_service_account_key};
@@ -7,6 +10,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use hyper::Client;
 use hyper_rustls::HttpsConnectorBuilder;
+use crate::a1::A1Range;
 
 // Stub for AuthManager (replace with real import and implementation)
 pub struct AuthManager;
@@ -17,6 +21,8 @@ pub struct GoogleApiClient {
     pub sheets: Arc<Mutex<Sheets<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>>>,
     pub docs: Arc<Mutex<Docs<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>>>,
     pub calendar: Arc<Mutex<CalendarHub<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>>>,
+    pub journal: Option<crate::journal::Journal>, // undo log for sheet writes, see `with_journal`
+    pub access: crate::access::AccessPolicy, // files and calendars this client may touch
 }
 
 impl GoogleApiClient {
@@ -43,50 +49,239 @@ impl GoogleApiClient {
             sheets: Arc::new(Mutex::new(sheets)),
             docs: Arc::new(Mutex::new(docs)),
             calendar: Arc::new(Mutex::new(calendar)),
+            journal: None,
+            access: crate::access::AccessPolicy::default(),
         }
     }
 
+    pub fn with_access_policy(mut self, access: crate::access::AccessPolicy) -> Self {
+        self.access = access;
+        self
+    }
+
+    // Parent folder IDs of a Drive file (empty for items at the root of a drive)
+    async fn file_parents(&self, file_id: &str) -> Result<Vec<String>, String> {
+        let drive = self.drive.lock().await;
+        let result = drive.files().get(file_id)
+            .param("fields", "parents")
+            .supports_all_drives(true)
+            .doit().await;
+        match result {
+            Ok((_, file)) => Ok(file.parents.unwrap_or_default()),
+            Err(e) => Err(format!("Drive API error: {e:?}")),
+        }
+    }
+
+    // Whether the access policy allows `file_id`, walking up its parents to find an allowed folder
+    async fn file_accessible(&self, file_id: &str) -> Result<bool, String> {
+        if self.access.file_listed(file_id) {
+            return Ok(true);
+        }
+        let mut frontier = vec![file_id.to_string()];
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..crate::access::MAX_FOLDER_DEPTH {
+            let mut next = Vec::new();
+            for id in &frontier {
+                for parent in self.file_parents(id).await? {
+                    if self.access.folder_allowed(&parent) {
+                        self.access.grant(file_id);
+                        return Ok(true);
+                    }
+                    if seen.insert(parent.clone()) {
+                        next.push(parent);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        Ok(false)
+    }
+
+    pub async fn check_file_access(&self, file_id: &str) -> Result<(), String> {
+        if self.file_accessible(file_id).await? {
+            Ok(())
+        } else {
+            Err(format!("Access denied: {} is not an allowed file and is not inside an allowed folder (access.*_ids)", file_id))
+        }
+    }
+
+    // New files must go into an allowed folder once file access is restricted
+    pub async fn check_new_file(&self, parent_folder_id: Option<&str>) -> Result<(), String> {
+        match parent_folder_id {
+            Some(parent) => self.check_file_access(parent).await,
+            None if self.access.restricts_files() => Err("Access denied: new files would be created outside the allowed folders (access.folder_ids)".to_string()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn check_calendar_access(&self, calendar_id: &str) -> Result<(), String> {
+        if self.access.calendar_allowed(calendar_id) {
+            Ok(())
+        } else {
+            Err(format!("Access denied: calendar {} is not in access.calendar_ids", calendar_id))
+        }
+    }
+
+    // Drop list results the access policy does not allow
+    async fn filter_accessible<T>(&self, items: Vec<T>, id: impl Fn(&T) -> &str) -> Result<Vec<T>, String> {
+        if !self.access.restricts_files() {
+            return Ok(items);
+        }
+        let mut kept = Vec::new();
+        for item in items {
+            if self.file_accessible(id(&item)).await? {
+                kept.push(item);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Records sheet writes made through this client in `journal` so they can be undone.
+    pub fn with_journal(mut self, journal: crate::journal::Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
     pub async fn list_spreadsheets(&self) -> Result<Vec<String>, String> {
         // Replace with real Google Drive API call
-        Ok(vec!["Sheet1".to_string(), "Sheet2".to_string()])
+        self.filter_accessible(vec!["Sheet1".to_string(), "Sheet2".to_string()], |id| id.as_str()).await
     }
 
     pub async fn create_spreadsheet(&self, title: &str) -> Result<String, String> {
+        self.check_new_file(None).await?;
         // Replace with real Google Sheets API call
         Ok(format!("created-{}", title))
     }
 
     pub async fn get_sheet_data(&self, spreadsheet_id: &str, range: &str) -> Result<Vec<Vec<String>>, String> {
+        self.check_file_access(spreadsheet_id).await?;
         // Replace with real Google Sheets API call
         Ok(vec![vec!["A1".to_string(), "B1".to_string()], vec!["A2".to_string(), "B2".to_string()]])
     }
 
+    // Write `values` at `range` with values.update, parsed as if typed into the UI. Returns the
+    // number of cells updated.
     pub async fn set_sheet_data(&self, spreadsheet_id: &str, range: &str, values: Vec<Vec<String>>) -> Result<usize, String> {
-        // Replace with real Google Sheets API call
-        Ok(values.iter().map(|row| row.len()).sum()) // Return total cells updated
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let value_range = ValueRange {
+            range: Some(range.to_string()),
+            values: Some(values.into_iter()
+                .map(|row| row.into_iter().map(serde_json::Value::String).collect())
+                .collect()),
+            ..Default::default()
+        };
+        let result = sheets.spreadsheets().values_update(value_range, spreadsheet_id, range)
+            .value_input_option("USER_ENTERED")
+            .doit().await;
+        match result {
+            Ok((_, resp)) => Ok(resp.updated_cells.unwrap_or(0) as usize),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
+    }
+
+    // Append rows after the last row of the table found at `range`, inserting new rows.
+    // Returns the cells written and the A1 range they landed in.
+    pub async fn append_sheet_data(&self, spreadsheet_id: &str, range: &str, values: Vec<Vec<String>>) -> Result<(usize, String), String> {
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let value_range = ValueRange {
+            values: Some(values.into_iter()
+                .map(|row| row.into_iter().map(serde_json::Value::String).collect())
+                .collect()),
+            ..Default::default()
+        };
+        let result = sheets.spreadsheets().values_append(value_range, spreadsheet_id, range)
+            .value_input_option("USER_ENTERED")
+            .insert_data_option("INSERT_ROWS")
+            .doit().await;
+        match result {
+            Ok((_, resp)) => {
+                let updates = resp.updates.unwrap_or_default();
+                Ok((updates.updated_cells.unwrap_or(0) as usize, updates.updated_range.unwrap_or_else(|| range.to_string())))
+            }
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
     }
 
     pub async fn batch_get_sheet_data(&self, spreadsheet_id: &str, ranges: &Vec<String>) -> Result<Vec<Vec<Vec<String>>>, String> {
+        self.check_file_access(spreadsheet_id).await?;
         // Replace with real Google Sheets API call
         Ok(ranges.iter().map(|_| vec![vec!["A".to_string(), "B".to_string()]]).collect())
     }
 
+    // Read ranges with an explicit valueRenderOption (FORMATTED_VALUE, UNFORMATTED_VALUE or FORMULA).
+    // Each result carries the range the API actually returned, e.g. `Sheet1!A1:D20`.
+    pub async fn get_values_rendered(&self, spreadsheet_id: &str, ranges: &[String], render: &str) -> Result<Vec<(String, Vec<Vec<String>>)>, String> {
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let mut call = sheets.spreadsheets().values_batch_get(spreadsheet_id)
+            .value_render_option(render);
+        for range in ranges {
+            call = call.add_ranges(range);
+        }
+        match call.doit().await {
+            Ok((_, resp)) => Ok(resp.value_ranges.unwrap_or_default().into_iter().map(|vr| {
+                let values = vr.values.unwrap_or_default().into_iter()
+                    .map(|row| row.iter().map(crate::table::cell_string).collect())
+                    .collect();
+                (vr.range.unwrap_or_default(), values)
+            }).collect()),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
+    }
+
+    // Write each (range, values) pair in one values.batchUpdate; values are parsed as if typed
+    // into the UI, so formulas stay formulas. Returns the number of cells updated.
     pub async fn batch_set_sheet_data(&self, spreadsheet_id: &str, updates: &Vec<(String, Vec<Vec<String>>)>) -> Result<usize, String> {
-        // Replace with real Google Sheets API call
-        Ok(updates.iter().map(|(_, values)| values.iter().map(|row| row.len()).sum::<usize>()).sum())
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let request = google_sheets4::api::BatchUpdateValuesRequest {
+            data: Some(updates.iter().map(|(range, values)| ValueRange {
+                range: Some(range.clone()),
+                values: Some(values.iter()
+                    .map(|row| row.iter().cloned().map(serde_json::Value::String).collect())
+                    .collect()),
+                ..Default::default()
+            }).collect()),
+            value_input_option: Some("USER_ENTERED".to_string()),
+            ..Default::default()
+        };
+        match sheets.spreadsheets().values_batch_update(request, spreadsheet_id).doit().await {
+            Ok((_, resp)) => Ok(resp.total_updated_cells.unwrap_or(0) as usize),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
+    }
+
+    // Clear the values (not formatting) in `range`; returns the range the API cleared.
+    pub async fn clear_values(&self, spreadsheet_id: &str, range: &str) -> Result<String, String> {
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let result = sheets.spreadsheets()
+            .values_clear(google_sheets4::api::ClearValuesRequest::default(), spreadsheet_id, range)
+            .doit().await;
+        match result {
+            Ok((_, resp)) => Ok(resp.cleared_range.unwrap_or_default()),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
     }
 
     pub async fn list_drive_files(&self) -> Result<Vec<String>, String> {
         // Replace with real Google Drive API call
-        Ok(vec!["file1_id".to_string(), "file2_id".to_string()])
+        self.filter_accessible(vec!["file1_id".to_string(), "file2_id".to_string()], |id| id.as_str()).await
     }
 
     pub async fn get_drive_file_metadata(&self, file_id: &str) -> Result<(String, String, Option<u64>), String> {
+        self.check_file_access(file_id).await?;
         // Replace with real Google Drive API call
         Ok((format!("File_{}", file_id), "application/vnd.google-apps.spreadsheet".to_string(), Some(1024)))
     }
 
     pub async fn list_permissions(&self, resource_id: &str, _resource_type: &crate::models::ResourceType) -> Result<Vec<crate::models::PermissionInfo>, String> {
+        self.check_file_access(resource_id).await?;
         use google_drive3::api::Scope;
         let mut drive = self.drive.lock().await;
         let result = drive.permissions().list(resource_id).doit().await;
@@ -104,14 +299,26 @@ impl GoogleApiClient {
         }
     }
 
-    pub async fn add_permission(&self, resource_id: &str, _resource_type: &crate::models::ResourceType, email: &str, role: &str, type_: &str) -> Result<String, String> {
+    /// `email` is the grantee's address, or the domain name for `domain` grants; it is
+    /// ignored for `anyone`. `message` is only sent when `notify` is set.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_permission(&self, resource_id: &str, _resource_type: &crate::models::ResourceType, email: &str, role: &str, type_: &str, notify: bool, message: Option<&str>) -> Result<String, String> {
+        self.check_file_access(resource_id).await?;
         use google_drive3::api::Permission;
         let mut drive = self.drive.lock().await;
         let mut perm = Permission::default();
-        perm.email_address = Some(email.to_string());
+        match type_ {
+            "domain" => perm.domain = Some(email.to_string()),
+            "anyone" => {}
+            _ => perm.email_address = Some(email.to_string()),
+        }
         perm.role = Some(role.to_string());
         perm.type_ = Some(type_.to_string());
-        let result = drive.permissions().create(perm, resource_id).send_notification_email(false).doit().await;
+        let mut call = drive.permissions().create(perm, resource_id).send_notification_email(notify);
+        if let (true, Some(message)) = (notify, message) {
+            call = call.email_message(message);
+        }
+        let result = call.doit().await;
         match result {
             Ok((_, p)) => Ok(p.id.unwrap_or_default()),
             Err(e) => Err(format!("Drive API error: {e:?}")),
@@ -119,6 +326,7 @@ impl GoogleApiClient {
     }
 
     pub async fn remove_permission(&self, resource_id: &str, _resource_type: &crate::models::ResourceType, permission_id: &str) -> Result<bool, String> {
+        self.check_file_access(resource_id).await?;
         let mut drive = self.drive.lock().await;
         let result = drive.permissions().delete(resource_id, permission_id).doit().await;
         match result {
@@ -127,6 +335,147 @@ impl GoogleApiClient {
         }
     }
 
+    // Export a Google Workspace file (e.g. a spreadsheet as XLSX) via Drive files.export
+    pub async fn export_file(&self, file_id: &str, mime_type: &str) -> Result<Vec<u8>, String> {
+        self.check_file_access(file_id).await?;
+        let drive = self.drive.lock().await;
+        let result = drive.files().export(file_id, mime_type).doit().await;
+        match result {
+            Ok(resp) => hyper::body::to_bytes(resp.into_body()).await
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| format!("Drive export read error: {e:?}")),
+            Err(e) => Err(format!("Drive API error: {e:?}")),
+        }
+    }
+
+    // Upload a file to Drive, converting it to a Google Sheet; returns the new file ID
+    pub async fn upload_as_spreadsheet(&self, name: &str, source_mime: &str, content: Vec<u8>, parent_folder_id: Option<&str>) -> Result<String, String> {
+        self.check_new_file(parent_folder_id).await?;
+        use google_drive3::api::File;
+        let drive = self.drive.lock().await;
+        let file = File {
+            name: Some(name.to_string()),
+            mime_type: Some("application/vnd.google-apps.spreadsheet".to_string()),
+            parents: parent_folder_id.map(|p| vec![p.to_string()]),
+            ..Default::default()
+        };
+        let source_mime: mime::Mime = source_mime.parse().map_err(|e| format!("Invalid MIME type {}: {e:?}", source_mime))?;
+        let result = drive.files().create(file)
+            .param("fields", "id")
+            .upload(std::io::Cursor::new(content), source_mime)
+            .await;
+        match result {
+            Ok((_, f)) => f.id.ok_or_else(|| "Drive API returned no file ID".to_string()),
+            Err(e) => Err(format!("Drive API error: {e:?}")),
+        }
+    }
+
+    // Fetch spreadsheet metadata restricted to the given fields mask
+    pub async fn get_spreadsheet(&self, spreadsheet_id: &str, fields: &str) -> Result<Spreadsheet, String> {
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let result = sheets.spreadsheets().get(spreadsheet_id)
+            .param("fields", fields)
+            .doit().await;
+        match result {
+            Ok((_, spreadsheet)) => Ok(spreadsheet),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
+    }
+
+    // Resolve a sheet title to its numeric sheetId; `None` means the first sheet
+    pub async fn sheet_id(&self, spreadsheet_id: &str, sheet_title: Option<&str>) -> Result<i32, String> {
+        let spreadsheet = self.get_spreadsheet(spreadsheet_id, "sheets.properties(sheetId,title)").await?;
+        let properties: Vec<SheetProperties> = spreadsheet.sheets.unwrap_or_default().into_iter()
+            .filter_map(|s| s.properties)
+            .collect();
+        let found = match sheet_title {
+            Some(title) => properties.into_iter().find(|p| p.title.as_deref() == Some(title)),
+            None => properties.into_iter().next(),
+        };
+        found.and_then(|p| p.sheet_id)
+            .ok_or_else(|| format!("Sheet not found: {}", sheet_title.unwrap_or("<first sheet>")))
+    }
+
+    // Fetch grid data (per-cell values, formulas, notes...) for one range, restricted to the given fields mask
+    pub async fn get_grid_data(&self, spreadsheet_id: &str, range: &str, fields: &str) -> Result<Spreadsheet, String> {
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let result = sheets.spreadsheets().get(spreadsheet_id)
+            .add_ranges(range)
+            .include_grid_data(true)
+            .param("fields", fields)
+            .doit().await;
+        match result {
+            Ok((_, spreadsheet)) => Ok(spreadsheet),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
+    }
+
+    pub async fn sheet_titles(&self, spreadsheet_id: &str) -> Result<Vec<String>, String> {
+        let spreadsheet = self.get_spreadsheet(spreadsheet_id, "sheets.properties.title").await?;
+        Ok(spreadsheet.sheets.unwrap_or_default().into_iter()
+            .filter_map(|s| s.properties.and_then(|p| p.title))
+            .collect())
+    }
+
+    // Convert an A1 range into a GridRange by resolving its sheet title.
+    // A bare name that matches no sheet is looked up as a named range.
+    pub async fn grid_range(&self, spreadsheet_id: &str, range: &A1Range) -> Result<GridRange, String> {
+        Ok(self.resolve_range(spreadsheet_id, range).await?.0)
+    }
+
+    // Like `grid_range`, but also returns the range in concrete A1 form (named ranges expanded)
+    pub async fn resolve_range(&self, spreadsheet_id: &str, range: &A1Range) -> Result<(GridRange, A1Range), String> {
+        let spreadsheet = self.get_spreadsheet(spreadsheet_id, RESOLVE_FIELDS).await?;
+        resolve_in(&spreadsheet, range)
+    }
+
+    pub async fn list_protected_ranges(&self, spreadsheet_id: &str) -> Result<Vec<(ProtectedRange, Option<String>)>, String> {
+        let spreadsheet = self.get_spreadsheet(spreadsheet_id, "sheets(properties(title),protectedRanges)").await?;
+        Ok(spreadsheet.sheets.unwrap_or_default().into_iter().flat_map(|sheet| {
+            let title = sheet.properties.and_then(|p| p.title);
+            sheet.protected_ranges.unwrap_or_default().into_iter().map(move |p| (p, title.clone()))
+        }).collect())
+    }
+
+    pub async fn list_named_ranges(&self, spreadsheet_id: &str) -> Result<Vec<(NamedRange, Option<String>)>, String> {
+        let spreadsheet = self.get_spreadsheet(spreadsheet_id, "sheets.properties(sheetId,title),namedRanges").await?;
+        let properties: Vec<SheetProperties> = spreadsheet.sheets.unwrap_or_default().into_iter()
+            .filter_map(|s| s.properties)
+            .collect();
+        Ok(spreadsheet.named_ranges.unwrap_or_default().into_iter().map(|n| {
+            // GridRange omits sheetId when it is 0
+            let sheet_id = n.range.as_ref().and_then(|r| r.sheet_id).unwrap_or(0);
+            let title = properties.iter().find(|p| p.sheet_id == Some(sheet_id)).and_then(|p| p.title.clone());
+            (n, title)
+        }).collect())
+    }
+
+    pub async fn batch_update(&self, spreadsheet_id: &str, requests: Vec<Request>) -> Result<BatchUpdateSpreadsheetResponse, String> {
+        let batch_req = BatchUpdateSpreadsheetRequest { requests: Some(requests), ..Default::default() };
+        self.batch_update_with(spreadsheet_id, batch_req).await
+    }
+
+    // Full-control variant of `batch_update` (response options such as includeSpreadsheetInResponse)
+    pub async fn batch_update_with(&self, spreadsheet_id: &str, batch_req: BatchUpdateSpreadsheetRequest) -> Result<BatchUpdateSpreadsheetResponse, String> {
+        self.check_file_access(spreadsheet_id).await?;
+        let sheets = self.sheets.lock().await;
+        let result = sheets.spreadsheets().batch_update(batch_req, spreadsheet_id).doit().await;
+        match result {
+            Ok((_, resp)) => Ok(resp),
+            Err(e) => Err(format!("Sheets API error: {e:?}")),
+        }
+    }
+
+    pub async fn get_conditional_formats(&self, spreadsheet_id: &str, sheet_title: &str) -> Result<Vec<ConditionalFormatRule>, String> {
+        let spreadsheet = self.get_spreadsheet(spreadsheet_id, "sheets(properties(title),conditionalFormats)").await?;
+        spreadsheet.sheets.unwrap_or_default().into_iter()
+            .find(|s| s.properties.as_ref().and_then(|p| p.title.as_deref()) == Some(sheet_title))
+            .map(|s| s.conditional_formats.unwrap_or_default())
+            .ok_or_else(|| format!("Sheet not found: {}", sheet_title))
+    }
+
     // List Google Docs files via Drive
     pub async fn list_docs(&self) -> Result<Vec<(String, String)>, anyhow::Error> {
         use google_drive3::api::Scope;
@@ -144,6 +493,42 @@ impl GoogleApiClient {
                 }
             })
             .collect();
-        Ok(docs)
+        drop(drive); // filter_accessible looks up parents through the same hub
+        self.filter_accessible(docs, |(id, _)| id.as_str()).await.map_err(anyhow::Error::msg)
+    }
+}
+
+/// Fields of `spreadsheets.get` that `resolve_in` needs.
+pub const RESOLVE_FIELDS: &str = "sheets.properties(sheetId,title),namedRanges(name,range)";
+
+/// Resolves `range` against already fetched spreadsheet metadata (see `RESOLVE_FIELDS`),
+/// so callers checking several ranges need only one `spreadsheets.get`.
+pub fn resolve_in(spreadsheet: &Spreadsheet, range: &A1Range) -> Result<(GridRange, A1Range), String> {
+    let properties: Vec<&SheetProperties> = spreadsheet.sheets.iter().flatten()
+        .filter_map(|s| s.properties.as_ref())
+        .collect();
+    let sheet = match range.sheet.as_deref() {
+        Some(title) => properties.iter().find(|p| p.title.as_deref() == Some(title)),
+        None => properties.first(),
+    };
+    if let Some(sheet_id) = sheet.and_then(|p| p.sheet_id) {
+        let mut concrete = range.clone();
+        concrete.sheet = sheet.and_then(|p| p.title.clone());
+        return Ok((range.to_grid_range(sheet_id), concrete));
+    }
+    if range.is_bare_name() {
+        let name = range.sheet.as_deref().unwrap_or_default();
+        let named = spreadsheet.named_ranges.iter().flatten()
+            .find(|n| n.name.as_deref() == Some(name))
+            .and_then(|n| n.range.clone());
+        if let Some(grid) = named {
+            let title = properties.iter()
+                .find(|p| p.sheet_id == grid.sheet_id.or(Some(0)))
+                .and_then(|p| p.title.as_deref());
+            let concrete = A1Range::from_grid_range(&grid, title);
+            return Ok((grid, concrete));
+        }
+        return Err(format!("No sheet or named range called {}", name));
     }
+    Err(format!("Sheet not found: {}", range.sheet.as_deref().unwrap_or("<first sheet>")))
 }
@@ -1,8 +1,8 @@
-use axum::{routing::post, Router, Json};
+use axum::{http::StatusCode, routing::{post, MethodRouter}, Router, Json};
 use std::sync::Arc;
 use crate::models::{
-    McpHandler, EchoHandler, EchoRequest, EchoResponse, EchoError,
-    ListSpreadsheetsHandler, ListSpreadsheetsRequest, ListSpreadsheetsResponse, SpreadsheetError,
+    McpHandler, EchoHandler, EchoRequest, EchoResponse,
+    ListSpreadsheetsHandler, ListSpreadsheetsRequest, ListSpreadsheetsResponse,
     CreateSpreadsheetHandler, CreateSpreadsheetRequest, CreateSpreadsheetResponse,
     GetSheetDataHandler, GetSheetDataRequest, GetSheetDataResponse,
     SetSheetDataHandler, SetSheetDataRequest, SetSheetDataResponse,
@@ -13,13 +13,59 @@ use crate::models::{
     ListPermissionsHandler, ListPermissionsRequest, ListPermissionsResponse,
     AddPermissionHandler, AddPermissionRequest, AddPermissionResponse,
     RemovePermissionHandler, RemovePermissionRequest, RemovePermissionResponse,
-    GetDocumentHandler, GetDocumentRequest, GetDocumentResponse, DocsError,
+    GetDocumentHandler, GetDocumentRequest, GetDocumentResponse,
     CreateDocumentHandler, CreateDocumentRequest, CreateDocumentResponse,
     AppendTextHandler, AppendTextRequest, AppendTextResponse,
     ListDocsHandler, ListDocsRequest, ListDocsResponse,
-    GetCalendarEventHandler, CreateCalendarEventHandler, ListCalendarEventsHandler,
+    GetCalendarEventHandler, GetCalendarEventRequest, GetCalendarEventResponse,
+    CreateCalendarEventHandler, CreateCalendarEventRequest, CreateCalendarEventResponse,
+    ListCalendarEventsHandler, ListCalendarEventsRequest, ListCalendarEventsResponse,
+    ListConditionalFormatsHandler, ListConditionalFormatsRequest, ListConditionalFormatsResponse,
+    AddConditionalFormatHandler, AddConditionalFormatRequest, AddConditionalFormatResponse,
+    UpdateConditionalFormatHandler, UpdateConditionalFormatRequest, UpdateConditionalFormatResponse,
+    DeleteConditionalFormatHandler, DeleteConditionalFormatRequest, DeleteConditionalFormatResponse,
+    SetDataValidationHandler, SetDataValidationRequest, SetDataValidationResponse,
+    ClearDataValidationHandler, ClearDataValidationRequest, ClearDataValidationResponse,
+    ListNamedRangesHandler, ListNamedRangesRequest, ListNamedRangesResponse,
+    CreateNamedRangeHandler, CreateNamedRangeRequest, CreateNamedRangeResponse,
+    UpdateNamedRangeHandler, UpdateNamedRangeRequest, UpdateNamedRangeResponse,
+    DeleteNamedRangeHandler, DeleteNamedRangeRequest, DeleteNamedRangeResponse,
+    ListProtectedRangesHandler, ListProtectedRangesRequest, ListProtectedRangesResponse,
+    AddProtectedRangeHandler, AddProtectedRangeRequest, AddProtectedRangeResponse,
+    UpdateProtectedRangeHandler, UpdateProtectedRangeRequest, UpdateProtectedRangeResponse,
+    RemoveProtectedRangeHandler, RemoveProtectedRangeRequest, RemoveProtectedRangeResponse,
+    CreateChartHandler, CreateChartRequest, CreateChartResponse,
+    UpdateChartHandler, UpdateChartRequest, UpdateChartResponse,
+    DeleteChartHandler, DeleteChartRequest, DeleteChartResponse,
+    SheetsBatchUpdateHandler, SheetsBatchUpdateRequest, SheetsBatchUpdateResponse,
+    ReadTableHandler, ReadTableRequest, ReadTableResponse,
+    UpsertRowsHandler, UpsertRowsRequest, UpsertRowsResponse,
+    QuerySheetHandler, QuerySheetRequest, QuerySheetResponse,
+    FindReplaceHandler, FindReplaceRequest, FindReplaceResponse,
+    SearchCellsHandler, SearchCellsRequest, SearchCellsResponse,
+    SortRangeHandler, SortRangeRequest, SortRangeResponse,
+    SetBasicFilterHandler, SetBasicFilterRequest, SetBasicFilterResponse,
+    ClearBasicFilterHandler, ClearBasicFilterRequest, ClearBasicFilterResponse,
+    CreateFilterViewHandler, CreateFilterViewRequest, CreateFilterViewResponse,
+    DeleteFilterViewHandler, DeleteFilterViewRequest, DeleteFilterViewResponse,
+    GetCellsHandler, GetCellsRequest, GetCellsResponse,
+    SetNoteHandler, SetNoteRequest, SetNoteResponse,
+    ClearNotesHandler, ClearNotesRequest, ClearNotesResponse,
+    SetHyperlinkHandler, SetHyperlinkRequest, SetHyperlinkResponse,
+    MergeCellsHandler, MergeCellsRequest, MergeCellsResponse,
+    UnmergeCellsHandler, UnmergeCellsRequest, UnmergeCellsResponse,
+    SetFrozenHandler, SetFrozenRequest, SetFrozenResponse,
+    ImportCsvHandler, ImportCsvRequest, ImportCsvResponse,
+    ExportRangeHandler, ExportRangeRequest, ExportRangeResponse,
+    UploadSpreadsheetHandler, UploadSpreadsheetRequest, UploadSpreadsheetResponse,
+    SnapshotRangeHandler, SnapshotRangeRequest, RangeSnapshot,
+    DiffRangesHandler, DiffRangesRequest, DiffRangesResponse,
+    ClearRangeHandler, ClearRangeRequest, ClearRangeResponse,
+    ListOperationsHandler, ListOperationsRequest, ListOperationsResponse,
+    UndoOperationHandler, UndoOperationRequest, UndoOperationResponse,
 };
 use crate::google_api_client::GoogleApiClient;
+use crate::config::AppConfig;
 
 async fn echo_route(Json(req): Json<EchoRequest>) -> Result<Json<EchoResponse>, String> {
     let handler = EchoHandler;
@@ -29,7 +75,6 @@ async fn echo_route(Json(req): Json<EchoRequest>) -> Result<Json<EchoResponse>,
 }
 
 async fn list_spreadsheets_route(
-    Json(_): Json<ListSpreadsheetsRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
 ) -> Result<Json<ListSpreadsheetsResponse>, String> {
     let handler = ListSpreadsheetsHandler { client };
@@ -39,8 +84,8 @@ async fn list_spreadsheets_route(
 }
 
 async fn create_spreadsheet_route(
-    Json(req): Json<CreateSpreadsheetRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<CreateSpreadsheetRequest>,
 ) -> Result<Json<CreateSpreadsheetResponse>, String> {
     let handler = CreateSpreadsheetHandler { client };
     handler.handle(req).await
@@ -49,8 +94,8 @@ async fn create_spreadsheet_route(
 }
 
 async fn get_sheet_data_route(
-    Json(req): Json<GetSheetDataRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<GetSheetDataRequest>,
 ) -> Result<Json<GetSheetDataResponse>, String> {
     let handler = GetSheetDataHandler { client };
     handler.handle(req).await
@@ -59,8 +104,8 @@ async fn get_sheet_data_route(
 }
 
 async fn set_sheet_data_route(
-    Json(req): Json<SetSheetDataRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SetSheetDataRequest>,
 ) -> Result<Json<SetSheetDataResponse>, String> {
     let handler = SetSheetDataHandler { client };
     handler.handle(req).await
@@ -69,8 +114,8 @@ async fn set_sheet_data_route(
 }
 
 async fn batch_get_sheet_data_route(
-    Json(req): Json<BatchGetSheetDataRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<BatchGetSheetDataRequest>,
 ) -> Result<Json<BatchGetSheetDataResponse>, String> {
     let handler = BatchGetSheetDataHandler { client };
     handler.handle(req).await
@@ -79,8 +124,8 @@ async fn batch_get_sheet_data_route(
 }
 
 async fn batch_set_sheet_data_route(
-    Json(req): Json<BatchSetSheetDataRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<BatchSetSheetDataRequest>,
 ) -> Result<Json<BatchSetSheetDataResponse>, String> {
     let handler = BatchSetSheetDataHandler { client };
     handler.handle(req).await
@@ -89,7 +134,6 @@ async fn batch_set_sheet_data_route(
 }
 
 async fn list_drive_files_route(
-    Json(_): Json<ListDriveFilesRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
 ) -> Result<Json<ListDriveFilesResponse>, String> {
     let handler = ListDriveFilesHandler { client };
@@ -99,8 +143,8 @@ async fn list_drive_files_route(
 }
 
 async fn get_drive_file_metadata_route(
-    Json(req): Json<GetDriveFileMetadataRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<GetDriveFileMetadataRequest>,
 ) -> Result<Json<GetDriveFileMetadataResponse>, String> {
     let handler = GetDriveFileMetadataHandler { client };
     handler.handle(req).await
@@ -109,8 +153,8 @@ async fn get_drive_file_metadata_route(
 }
 
 async fn list_permissions_route(
-    Json(req): Json<ListPermissionsRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListPermissionsRequest>,
 ) -> Result<Json<ListPermissionsResponse>, String> {
     let handler = ListPermissionsHandler { client };
     handler.handle(req).await
@@ -119,28 +163,30 @@ async fn list_permissions_route(
 }
 
 async fn add_permission_route(
-    Json(req): Json<AddPermissionRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    axum::extract::Extension(config): axum::extract::Extension<Arc<AppConfig>>,
+    Json(req): Json<AddPermissionRequest>,
 ) -> Result<Json<AddPermissionResponse>, String> {
-    let handler = AddPermissionHandler { client };
+    let handler = AddPermissionHandler { client, config };
     handler.handle(req).await
         .map(Json)
         .map_err(|e| format!("AddPermission error: {:?}", e))
 }
 
 async fn remove_permission_route(
-    Json(req): Json<RemovePermissionRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    axum::extract::Extension(config): axum::extract::Extension<Arc<AppConfig>>,
+    Json(req): Json<RemovePermissionRequest>,
 ) -> Result<Json<RemovePermissionResponse>, String> {
-    let handler = RemovePermissionHandler { client };
+    let handler = RemovePermissionHandler { client, config };
     handler.handle(req).await
         .map(Json)
         .map_err(|e| format!("RemovePermission error: {:?}", e))
 }
 
 async fn get_document_route(
-    Json(req): Json<GetDocumentRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<GetDocumentRequest>,
 ) -> Result<Json<GetDocumentResponse>, String> {
     let handler = GetDocumentHandler { client };
     handler.handle(req).await
@@ -149,8 +195,8 @@ async fn get_document_route(
 }
 
 async fn create_document_route(
-    Json(req): Json<CreateDocumentRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<CreateDocumentRequest>,
 ) -> Result<Json<CreateDocumentResponse>, String> {
     let handler = CreateDocumentHandler { client };
     handler.handle(req).await
@@ -159,8 +205,8 @@ async fn create_document_route(
 }
 
 async fn append_text_route(
-    Json(req): Json<AppendTextRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<AppendTextRequest>,
 ) -> Result<Json<AppendTextResponse>, String> {
     let handler = AppendTextHandler { client };
     handler.handle(req).await
@@ -169,18 +215,18 @@ async fn append_text_route(
 }
 
 async fn list_docs_route(
-    Json(_): Json<ListDocsRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListDocsRequest>,
 ) -> Result<Json<ListDocsResponse>, String> {
     let handler = ListDocsHandler { client };
-    handler.handle(ListDocsRequest).await
+    handler.handle(req).await
         .map(Json)
         .map_err(|e| format!("ListDocs error: {:?}", e))
 }
 
 async fn get_calendar_event_route(
-    Json(req): Json<GetCalendarEventRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<GetCalendarEventRequest>,
 ) -> Result<Json<GetCalendarEventResponse>, String> {
     let handler = GetCalendarEventHandler { client };
     handler.handle(req).await
@@ -189,8 +235,8 @@ async fn get_calendar_event_route(
 }
 
 async fn create_calendar_event_route(
-    Json(req): Json<CreateCalendarEventRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<CreateCalendarEventRequest>,
 ) -> Result<Json<CreateCalendarEventResponse>, String> {
     let handler = CreateCalendarEventHandler { client };
     handler.handle(req).await
@@ -199,8 +245,8 @@ async fn create_calendar_event_route(
 }
 
 async fn list_calendar_events_route(
-    Json(req): Json<ListCalendarEventsRequest>,
     axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListCalendarEventsRequest>,
 ) -> Result<Json<ListCalendarEventsResponse>, String> {
     let handler = ListCalendarEventsHandler { client };
     handler.handle(req).await
@@ -208,39 +254,542 @@ async fn list_calendar_events_route(
         .map_err(|e| format!("ListCalendarEvents error: {:?}", e))
 }
 
-pub fn example_router(client: Arc<GoogleApiClient>) -> Router {
-    Router::new()
+async fn list_conditional_formats_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListConditionalFormatsRequest>,
+) -> Result<Json<ListConditionalFormatsResponse>, String> {
+    let handler = ListConditionalFormatsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ListConditionalFormats error: {:?}", e))
+}
+
+async fn add_conditional_format_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<AddConditionalFormatRequest>,
+) -> Result<Json<AddConditionalFormatResponse>, String> {
+    let handler = AddConditionalFormatHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("AddConditionalFormat error: {:?}", e))
+}
+
+async fn update_conditional_format_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UpdateConditionalFormatRequest>,
+) -> Result<Json<UpdateConditionalFormatResponse>, String> {
+    let handler = UpdateConditionalFormatHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UpdateConditionalFormat error: {:?}", e))
+}
+
+async fn delete_conditional_format_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<DeleteConditionalFormatRequest>,
+) -> Result<Json<DeleteConditionalFormatResponse>, String> {
+    let handler = DeleteConditionalFormatHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("DeleteConditionalFormat error: {:?}", e))
+}
+
+async fn set_data_validation_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SetDataValidationRequest>,
+) -> Result<Json<SetDataValidationResponse>, String> {
+    let handler = SetDataValidationHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SetDataValidation error: {:?}", e))
+}
+
+async fn clear_data_validation_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ClearDataValidationRequest>,
+) -> Result<Json<ClearDataValidationResponse>, String> {
+    let handler = ClearDataValidationHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ClearDataValidation error: {:?}", e))
+}
+
+async fn list_named_ranges_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListNamedRangesRequest>,
+) -> Result<Json<ListNamedRangesResponse>, String> {
+    let handler = ListNamedRangesHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ListNamedRanges error: {:?}", e))
+}
+
+async fn create_named_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<CreateNamedRangeRequest>,
+) -> Result<Json<CreateNamedRangeResponse>, String> {
+    let handler = CreateNamedRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("CreateNamedRange error: {:?}", e))
+}
+
+async fn update_named_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UpdateNamedRangeRequest>,
+) -> Result<Json<UpdateNamedRangeResponse>, String> {
+    let handler = UpdateNamedRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UpdateNamedRange error: {:?}", e))
+}
+
+async fn delete_named_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<DeleteNamedRangeRequest>,
+) -> Result<Json<DeleteNamedRangeResponse>, String> {
+    let handler = DeleteNamedRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("DeleteNamedRange error: {:?}", e))
+}
+
+async fn list_protected_ranges_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListProtectedRangesRequest>,
+) -> Result<Json<ListProtectedRangesResponse>, String> {
+    let handler = ListProtectedRangesHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ListProtectedRanges error: {:?}", e))
+}
+
+async fn add_protected_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<AddProtectedRangeRequest>,
+) -> Result<Json<AddProtectedRangeResponse>, String> {
+    let handler = AddProtectedRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("AddProtectedRange error: {:?}", e))
+}
+
+async fn update_protected_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UpdateProtectedRangeRequest>,
+) -> Result<Json<UpdateProtectedRangeResponse>, String> {
+    let handler = UpdateProtectedRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UpdateProtectedRange error: {:?}", e))
+}
+
+async fn remove_protected_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<RemoveProtectedRangeRequest>,
+) -> Result<Json<RemoveProtectedRangeResponse>, String> {
+    let handler = RemoveProtectedRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("RemoveProtectedRange error: {:?}", e))
+}
+
+async fn create_chart_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<CreateChartRequest>,
+) -> Result<Json<CreateChartResponse>, String> {
+    let handler = CreateChartHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("CreateChart error: {:?}", e))
+}
+
+async fn update_chart_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UpdateChartRequest>,
+) -> Result<Json<UpdateChartResponse>, String> {
+    let handler = UpdateChartHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UpdateChart error: {:?}", e))
+}
+
+async fn delete_chart_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<DeleteChartRequest>,
+) -> Result<Json<DeleteChartResponse>, String> {
+    let handler = DeleteChartHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("DeleteChart error: {:?}", e))
+}
+
+async fn sheets_batch_update_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    axum::extract::Extension(config): axum::extract::Extension<Arc<AppConfig>>,
+    Json(req): Json<SheetsBatchUpdateRequest>,
+) -> Result<Json<SheetsBatchUpdateResponse>, String> {
+    let handler = SheetsBatchUpdateHandler { client, config };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SheetsBatchUpdate error: {:?}", e))
+}
+
+async fn read_table_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ReadTableRequest>,
+) -> Result<Json<ReadTableResponse>, String> {
+    let handler = ReadTableHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ReadTable error: {:?}", e))
+}
+
+async fn upsert_rows_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UpsertRowsRequest>,
+) -> Result<Json<UpsertRowsResponse>, String> {
+    let handler = UpsertRowsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UpsertRows error: {:?}", e))
+}
+
+async fn query_sheet_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<QuerySheetRequest>,
+) -> Result<Json<QuerySheetResponse>, String> {
+    let handler = QuerySheetHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("QuerySheet error: {:?}", e))
+}
+
+async fn find_replace_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<FindReplaceRequest>,
+) -> Result<Json<FindReplaceResponse>, String> {
+    let handler = FindReplaceHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("FindReplace error: {:?}", e))
+}
+
+async fn search_cells_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SearchCellsRequest>,
+) -> Result<Json<SearchCellsResponse>, String> {
+    let handler = SearchCellsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SearchCells error: {:?}", e))
+}
+
+async fn sort_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SortRangeRequest>,
+) -> Result<Json<SortRangeResponse>, String> {
+    let handler = SortRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SortRange error: {:?}", e))
+}
+
+async fn set_basic_filter_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SetBasicFilterRequest>,
+) -> Result<Json<SetBasicFilterResponse>, String> {
+    let handler = SetBasicFilterHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SetBasicFilter error: {:?}", e))
+}
+
+async fn clear_basic_filter_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ClearBasicFilterRequest>,
+) -> Result<Json<ClearBasicFilterResponse>, String> {
+    let handler = ClearBasicFilterHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ClearBasicFilter error: {:?}", e))
+}
+
+async fn create_filter_view_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<CreateFilterViewRequest>,
+) -> Result<Json<CreateFilterViewResponse>, String> {
+    let handler = CreateFilterViewHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("CreateFilterView error: {:?}", e))
+}
+
+async fn delete_filter_view_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<DeleteFilterViewRequest>,
+) -> Result<Json<DeleteFilterViewResponse>, String> {
+    let handler = DeleteFilterViewHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("DeleteFilterView error: {:?}", e))
+}
+
+async fn get_cells_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<GetCellsRequest>,
+) -> Result<Json<GetCellsResponse>, String> {
+    let handler = GetCellsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("GetCells error: {:?}", e))
+}
+
+async fn set_note_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SetNoteRequest>,
+) -> Result<Json<SetNoteResponse>, String> {
+    let handler = SetNoteHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SetNote error: {:?}", e))
+}
+
+async fn clear_notes_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ClearNotesRequest>,
+) -> Result<Json<ClearNotesResponse>, String> {
+    let handler = ClearNotesHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ClearNotes error: {:?}", e))
+}
+
+async fn set_hyperlink_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SetHyperlinkRequest>,
+) -> Result<Json<SetHyperlinkResponse>, String> {
+    let handler = SetHyperlinkHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SetHyperlink error: {:?}", e))
+}
+
+async fn merge_cells_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<MergeCellsRequest>,
+) -> Result<Json<MergeCellsResponse>, String> {
+    let handler = MergeCellsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("MergeCells error: {:?}", e))
+}
+
+async fn unmerge_cells_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UnmergeCellsRequest>,
+) -> Result<Json<UnmergeCellsResponse>, String> {
+    let handler = UnmergeCellsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UnmergeCells error: {:?}", e))
+}
+
+async fn set_frozen_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SetFrozenRequest>,
+) -> Result<Json<SetFrozenResponse>, String> {
+    let handler = SetFrozenHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SetFrozen error: {:?}", e))
+}
+
+async fn import_csv_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ImportCsvRequest>,
+) -> Result<Json<ImportCsvResponse>, String> {
+    let handler = ImportCsvHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ImportCsv error: {:?}", e))
+}
+
+async fn export_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ExportRangeRequest>,
+) -> Result<Json<ExportRangeResponse>, String> {
+    let handler = ExportRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ExportRange error: {:?}", e))
+}
+
+async fn upload_spreadsheet_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    axum::extract::Extension(config): axum::extract::Extension<Arc<AppConfig>>,
+    Json(req): Json<UploadSpreadsheetRequest>,
+) -> Result<Json<UploadSpreadsheetResponse>, String> {
+    let handler = UploadSpreadsheetHandler { client, config };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UploadSpreadsheet error: {:?}", e))
+}
+
+async fn snapshot_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<SnapshotRangeRequest>,
+) -> Result<Json<RangeSnapshot>, String> {
+    let handler = SnapshotRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("SnapshotRange error: {:?}", e))
+}
+
+async fn diff_ranges_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<DiffRangesRequest>,
+) -> Result<Json<DiffRangesResponse>, String> {
+    let handler = DiffRangesHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("DiffRanges error: {:?}", e))
+}
+
+async fn clear_range_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ClearRangeRequest>,
+) -> Result<Json<ClearRangeResponse>, String> {
+    let handler = ClearRangeHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ClearRange error: {:?}", e))
+}
+
+async fn list_operations_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<ListOperationsRequest>,
+) -> Result<Json<ListOperationsResponse>, String> {
+    let handler = ListOperationsHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("ListOperations error: {:?}", e))
+}
+
+async fn undo_operation_route(
+    axum::extract::Extension(client): axum::extract::Extension<Arc<GoogleApiClient>>,
+    Json(req): Json<UndoOperationRequest>,
+) -> Result<Json<UndoOperationResponse>, String> {
+    let handler = UndoOperationHandler { client };
+    handler.handle(req).await
+        .map(Json)
+        .map_err(|e| format!("UndoOperation error: {:?}", e))
+}
+
+async fn list_tools_route(
+    axum::extract::Extension(config): axum::extract::Extension<Arc<AppConfig>>,
+) -> Json<Vec<crate::tools::ToolInfo>> {
+    Json(crate::tools::ToolPolicy::from_config(&config.tools).allowed_tools())
+}
+
+/// Builds the router. Tools the configured policy does not permit are answered with
+/// 403 Forbidden instead of being dispatched, and are left out of `/tools/list`.
+pub fn example_router(client: Arc<GoogleApiClient>, config: Arc<AppConfig>) -> Router {
+    tool_router(config).layer(axum::extract::Extension(client))
+}
+
+/// The tool routes behind `example_router`, filtered by the configured `ToolPolicy`. Routes
+/// still need a `GoogleApiClient` extension layered on top before they can dispatch.
+pub fn tool_router(config: Arc<AppConfig>) -> Router {
+    let tools: Vec<(&str, &str, MethodRouter)> = vec![
+        ("list_spreadsheets", "/spreadsheets/list", post(list_spreadsheets_route)),
+        ("create_spreadsheet", "/spreadsheets/create", post(create_spreadsheet_route)),
+        ("get_sheet_data", "/sheets/get", post(get_sheet_data_route)),
+        ("set_sheet_data", "/sheets/set", post(set_sheet_data_route)),
+        ("batch_get_sheet_data", "/sheets/batch_get", post(batch_get_sheet_data_route)),
+        ("batch_set_sheet_data", "/sheets/batch_set", post(batch_set_sheet_data_route)),
+        ("list_drive_files", "/drive/list", post(list_drive_files_route)),
+        ("get_drive_file_metadata", "/drive/metadata", post(get_drive_file_metadata_route)),
+        ("list_permissions", "/drive/permissions/list", post(list_permissions_route)),
+        ("add_permission", "/drive/permissions/add", post(add_permission_route)),
+        ("remove_permission", "/drive/permissions/remove", post(remove_permission_route)),
+        ("get_document", "/docs/get", post(get_document_route)),
+        ("create_document", "/docs/create", post(create_document_route)),
+        ("append_text", "/docs/append", post(append_text_route)),
+        ("list_docs", "/docs/list", post(list_docs_route)),
+        ("get_calendar_event", "/calendar/get_event", post(get_calendar_event_route)),
+        ("create_calendar_event", "/calendar/create_event", post(create_calendar_event_route)),
+        ("list_calendar_events", "/calendar/list_events", post(list_calendar_events_route)),
+        ("list_conditional_formats", "/sheets/conditional_formats/list", post(list_conditional_formats_route)),
+        ("add_conditional_format", "/sheets/conditional_formats/add", post(add_conditional_format_route)),
+        ("update_conditional_format", "/sheets/conditional_formats/update", post(update_conditional_format_route)),
+        ("delete_conditional_format", "/sheets/conditional_formats/delete", post(delete_conditional_format_route)),
+        ("set_data_validation", "/sheets/data_validation/set", post(set_data_validation_route)),
+        ("clear_data_validation", "/sheets/data_validation/clear", post(clear_data_validation_route)),
+        ("list_named_ranges", "/sheets/named_ranges/list", post(list_named_ranges_route)),
+        ("create_named_range", "/sheets/named_ranges/create", post(create_named_range_route)),
+        ("update_named_range", "/sheets/named_ranges/update", post(update_named_range_route)),
+        ("delete_named_range", "/sheets/named_ranges/delete", post(delete_named_range_route)),
+        ("list_protected_ranges", "/sheets/protected_ranges/list", post(list_protected_ranges_route)),
+        ("add_protected_range", "/sheets/protected_ranges/add", post(add_protected_range_route)),
+        ("update_protected_range", "/sheets/protected_ranges/update", post(update_protected_range_route)),
+        ("remove_protected_range", "/sheets/protected_ranges/remove", post(remove_protected_range_route)),
+        ("create_chart", "/sheets/charts/create", post(create_chart_route)),
+        ("update_chart", "/sheets/charts/update", post(update_chart_route)),
+        ("delete_chart", "/sheets/charts/delete", post(delete_chart_route)),
+        ("sheets_batch_update", "/sheets/batch_update", post(sheets_batch_update_route)),
+        ("read_table", "/sheets/read_table", post(read_table_route)),
+        ("upsert_rows", "/sheets/upsert_rows", post(upsert_rows_route)),
+        ("query_sheet", "/sheets/query", post(query_sheet_route)),
+        ("find_replace", "/sheets/find_replace", post(find_replace_route)),
+        ("search_cells", "/sheets/search", post(search_cells_route)),
+        ("sort_range", "/sheets/sort", post(sort_range_route)),
+        ("set_basic_filter", "/sheets/basic_filter/set", post(set_basic_filter_route)),
+        ("clear_basic_filter", "/sheets/basic_filter/clear", post(clear_basic_filter_route)),
+        ("create_filter_view", "/sheets/filter_views/create", post(create_filter_view_route)),
+        ("delete_filter_view", "/sheets/filter_views/delete", post(delete_filter_view_route)),
+        ("get_cells", "/sheets/cells", post(get_cells_route)),
+        ("set_note", "/sheets/notes/set", post(set_note_route)),
+        ("clear_notes", "/sheets/notes/clear", post(clear_notes_route)),
+        ("set_hyperlink", "/sheets/hyperlink", post(set_hyperlink_route)),
+        ("merge_cells", "/sheets/merge", post(merge_cells_route)),
+        ("unmerge_cells", "/sheets/unmerge", post(unmerge_cells_route)),
+        ("set_frozen", "/sheets/frozen", post(set_frozen_route)),
+        ("import_csv", "/sheets/import_csv", post(import_csv_route)),
+        ("export_range", "/sheets/export", post(export_range_route)),
+        ("upload_spreadsheet", "/sheets/upload", post(upload_spreadsheet_route)),
+        ("snapshot_range", "/sheets/snapshot", post(snapshot_range_route)),
+        ("diff_ranges", "/sheets/diff", post(diff_ranges_route)),
+        ("clear_range", "/sheets/clear", post(clear_range_route)),
+        ("list_operations", "/operations/list", post(list_operations_route)),
+        ("undo_operation", "/operations/undo", post(undo_operation_route)),
+    ];
+    let policy = crate::tools::ToolPolicy::from_config(&config.tools);
+    let mut router = Router::new()
         .route("/echo", post(echo_route))
-        .route("/spreadsheets/list", post(list_spreadsheets_route))
-        .route("/spreadsheets/create", post(create_spreadsheet_route))
-        .route("/sheets/get", post(get_sheet_data_route))
-        .route("/sheets/set", post(set_sheet_data_route))
-        .route("/sheets/batch_get", post(batch_get_sheet_data_route))
-        .route("/sheets/batch_set", post(batch_set_sheet_data_route))
-        .route("/drive/list", post(list_drive_files_route))
-        .route("/drive/metadata", post(get_drive_file_metadata_route))
-        .route("/drive/permissions/list", post(list_permissions_route))
-        .route("/drive/permissions/add", post(add_permission_route))
-        .route("/drive/permissions/remove", post(remove_permission_route))
-        .route("/docs/get", post(get_document_route))
-        .route("/docs/create", post(create_document_route))
-        .route("/docs/append", post(append_text_route))
-        .route("/docs/list", post(list_docs_route))
-        .route("/calendar/get_event", post(get_calendar_event_route))
-        .route("/calendar/create_event", post(create_calendar_event_route))
-        .route("/calendar/list_events", post(list_calendar_events_route))
-        .layer(axum::extract::Extension(client))
+        .route("/tools/list", post(list_tools_route));
+    for (tool, path, route) in tools {
+        router = match policy.check(tool) {
+            Ok(()) => router.route(path, route),
+            Err(reason) => router.route(path, post(move || async move { (StatusCode::FORBIDDEN, reason) })),
+        };
+    }
+    router.layer(axum::extract::Extension(config))
 }
 
 // Example: Integrate the unified handler into a full Axum server
 #[tokio::main]
 pub async fn main() {
-    let client = Arc::new(GoogleApiClient::new(Arc::new(crate::google_api_client::AuthManager)).await);
-    let app = example_router(client);
+    let config = Arc::new(AppConfig::load(None).expect("Failed to load configuration"));
+    let mut client = GoogleApiClient::new(Arc::new(crate::google_api_client::AuthManager)).await
+        .with_access_policy(crate::access::AccessPolicy::from_config(&config.access));
+    if let Some(path) = &config.journal.path {
+        client = client.with_journal(crate::journal::Journal::new(path));
+    }
+    let client = Arc::new(client);
+    let app = example_router(client, config);
     let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
     println!("Listening on http://{}", addr);
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// The contents of a range just before a write, padded to the written extent so that
+/// restoring it also clears cells the write filled in.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RangeBackup {
+    pub range: String,
+    pub before: Vec<Vec<String>>, // formulas where the cell held one
+}
+
+/// One line of the journal file.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEntry {
+    Write {
+        id: String,
+        timestamp: u64, // seconds since the Unix epoch
+        tool: String,
+        spreadsheet_id: String,
+        ranges: Vec<RangeBackup>,
+    },
+    Undo {
+        id: String, // the write that was undone
+        undo_id: Option<String>, // the write that restored it, itself undoable
+        timestamp: u64,
+    },
+}
+
+/// A journaled write with its undo state folded in.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Operation {
+    pub id: String,
+    pub timestamp: u64,
+    pub tool: String,
+    pub spreadsheet_id: String,
+    pub ranges: Vec<RangeBackup>,
+    pub undone_by: Option<String>,
+}
+
+/// Append-only JSON Lines log of sheet writes. Entries are never rewritten; undoing an
+/// operation appends an `undo` entry instead.
+pub struct Journal {
+    path: PathBuf,
+    lock: Mutex<()>, // serializes appends from concurrent handlers
+}
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+fn now() -> std::time::Duration {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default()
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Journal { path: path.into(), lock: Mutex::new(()) }
+    }
+
+    fn append(&self, entry: &JournalEntry) -> Result<(), String> {
+        let line = serde_json::to_string(entry).map_err(|e| format!("Failed to encode journal entry: {}", e))?;
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)
+            .map_err(|e| format!("Failed to open journal {}: {}", self.path.display(), e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Failed to write journal {}: {}", self.path.display(), e))
+    }
+
+    /// Records a write and returns its operation ID.
+    pub fn record(&self, tool: &str, spreadsheet_id: &str, ranges: Vec<RangeBackup>) -> Result<String, String> {
+        let now = now();
+        let id = format!("op-{}-{}", now.as_millis(), SEQUENCE.fetch_add(1, Ordering::Relaxed));
+        self.append(&JournalEntry::Write {
+            id: id.clone(),
+            timestamp: now.as_secs(),
+            tool: tool.to_string(),
+            spreadsheet_id: spreadsheet_id.to_string(),
+            ranges,
+        })?;
+        Ok(id)
+    }
+
+    pub fn mark_undone(&self, id: &str, undo_id: Option<String>) -> Result<(), String> {
+        self.append(&JournalEntry::Undo { id: id.to_string(), undo_id, timestamp: now().as_secs() })
+    }
+
+    /// All recorded writes, oldest first. A missing journal file means no operations yet.
+    pub fn operations(&self) -> Result<Vec<Operation>, String> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to open journal {}: {}", self.path.display(), e)),
+        };
+        let mut operations: Vec<Operation> = Vec::new();
+        for (n, line) in std::io::BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("Failed to read journal {}: {}", self.path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: JournalEntry = serde_json::from_str(&line)
+                .map_err(|e| format!("Corrupt journal entry on line {}: {}", n + 1, e))?;
+            match entry {
+                JournalEntry::Write { id, timestamp, tool, spreadsheet_id, ranges } => {
+                    operations.push(Operation { id, timestamp, tool, spreadsheet_id, ranges, undone_by: None });
+                }
+                JournalEntry::Undo { id, undo_id, .. } => {
+                    if let Some(op) = operations.iter_mut().find(|op| op.id == id) {
+                        op.undone_by = Some(undo_id.unwrap_or_default());
+                    }
+                }
+            }
+        }
+        Ok(operations)
+    }
+}
+
+/// Pads `values` with blanks to at least `rows` x `cols`, keeping ragged rows at least as
+/// wide as they were.
+pub fn pad_grid(mut values: Vec<Vec<String>>, rows: usize, cols: usize) -> Vec<Vec<String>> {
+    if values.len() < rows {
+        values.resize(rows, Vec::new());
+    }
+    for row in values.iter_mut().take(rows) {
+        if row.len() < cols {
+            row.resize(cols, String::new());
+        }
+    }
+    values
+}
@@ -1,4 +1,17 @@
+pub mod a1;
+pub mod access;
 pub mod auth_manager;
 pub mod config;
+pub mod delimited;
+pub mod diff;
 pub mod models;
-pub mod google_api_client;
\ No newline at end of file
+pub mod google_api_client;
+pub mod journal;
+pub mod query;
+pub mod search;
+pub mod sharing;
+pub mod table;
+pub mod tools;
+pub mod validation;pub mod http_server;
+pub mod see_server;
+pub mod stdio_server;
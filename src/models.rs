@@ -1,12 +1,16 @@
+use crate::a1::A1Range;
 use crate::google_api_client::GoogleApiClient;
 use std::sync::Arc;
 use async_trait::async_trait;
 
 /// Example request and response types
+#[derive(serde::Deserialize)]
 pub struct EchoRequest {
+    #[serde(default)]
     pub message: String,
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct EchoResponse {
     pub echoed: String,
 }
@@ -38,21 +42,31 @@ impl McpHandler for EchoHandler {
 // Request/response types for business logic
 pub struct ListSpreadsheetsRequest;
 
+#[derive(serde::Serialize, Debug)]
 pub struct ListSpreadsheetsResponse {
     pub spreadsheets: Vec<String>,
 }
 
+#[derive(serde::Deserialize)]
 pub struct CreateSpreadsheetRequest {
     pub title: String,
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct CreateSpreadsheetResponse {
     pub id: String,
+    pub dry_run: Option<DryRunReport>,
 }
 
 #[derive(Debug)]
 pub enum SpreadsheetError {
     Api(String),
+    InvalidRequest(String),
+    Protected(String),
+    Forbidden(String),
+    Conflict(String), // a write precondition no longer holds
 }
 
 pub struct ListSpreadsheetsHandler {
@@ -85,32 +99,50 @@ impl McpHandler for CreateSpreadsheetHandler {
     type Error = SpreadsheetError;
 
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_new_file(None).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.dry_run {
+            let report = describe(format!("Would create spreadsheet {:?}", req.title), serde_json::json!({ "title": req.title }));
+            return Ok(CreateSpreadsheetResponse { dry_run: Some(report), ..Default::default() });
+        }
         self.client
             .create_spreadsheet(&req.title)
             .await
-            .map(|id| CreateSpreadsheetResponse { id })
+            .map(|id| CreateSpreadsheetResponse { id, dry_run: None })
             .map_err(SpreadsheetError::Api)
     }
 }
 
 // Get/Set sheet data types
+#[derive(serde::Deserialize)]
 pub struct GetSheetDataRequest {
     pub spreadsheet_id: String,
-    pub range: String, // e.g., "Sheet1!A1:C10"
+    pub range: String, // e.g., "Sheet1!A1:C10" or a named range such as "Totals"
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct GetSheetDataResponse {
     pub values: Vec<Vec<String>>,
+    pub content_hash: String, // pass back as `expected: Hash` to make a write conditional
 }
 
+#[derive(serde::Deserialize)]
 pub struct SetSheetDataRequest {
     pub spreadsheet_id: String,
-    pub range: String,
+    pub range: String, // A1 notation or a named range
     pub values: Vec<Vec<String>>,
+    #[serde(default)]
+    pub override_protection: bool, // write even if the range overlaps a protected range
+    #[serde(default)]
+    pub expected: Option<ExpectedContent>, // reject the write if the range no longer holds this
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct SetSheetDataResponse {
     pub updated_cells: usize,
+    pub operation_id: Option<String>, // journal entry for `undo_operation`, when journaling is on
+    pub dry_run: Option<DryRunReport>,
 }
 
 pub struct GetSheetDataHandler {
@@ -123,9 +155,10 @@ impl McpHandler for GetSheetDataHandler {
     type Response = GetSheetDataResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        self.client.get_sheet_data(&req.spreadsheet_id, &req.range).await
-            .map(|values| GetSheetDataResponse { values })
-            .map_err(SpreadsheetError::Api)
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        // Hashed as formatted values, matching what `check_expected` compares against.
+        read_values(&self.client, &req.spreadsheet_id, &req.range).await
+            .map(|values| GetSheetDataResponse { content_hash: crate::diff::content_hash(&values), values })
     }
 }
 
@@ -139,29 +172,55 @@ impl McpHandler for SetSheetDataHandler {
     type Response = SetSheetDataResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        self.client.set_sheet_data(&req.spreadsheet_id, &req.range, req.values).await
-            .map(|updated_cells| SetSheetDataResponse { updated_cells })
-            .map_err(SpreadsheetError::Api)
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if !req.override_protection {
+            ensure_unprotected(&self.client, &req.spreadsheet_id, &[(req.range.as_str(), &req.values)]).await?;
+        }
+        if let Some(expected) = &req.expected {
+            check_expected(&self.client, &req.spreadsheet_id, &req.range, expected).await?;
+        }
+        if req.dry_run {
+            let report = preview_writes(&self.client, &req.spreadsheet_id, &[(req.range.as_str(), &req.values)]).await?;
+            return Ok(SetSheetDataResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let backups = backup_ranges(&self.client, &req.spreadsheet_id, &[(req.range.as_str(), &req.values)]).await?;
+        let updated_cells = self.client.set_sheet_data(&req.spreadsheet_id, &req.range, req.values).await
+            .map_err(SpreadsheetError::Api)?;
+        let operation_id = journal_write(&self.client, "set_sheet_data", &req.spreadsheet_id, backups);
+        Ok(SetSheetDataResponse { updated_cells, operation_id, dry_run: None })
     }
 }
 
 // Batch operations
+#[derive(serde::Deserialize)]
 pub struct BatchGetSheetDataRequest {
     pub spreadsheet_id: String,
-    pub ranges: Vec<String>,
+    pub ranges: Vec<String>, // A1 notation or named ranges
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct BatchGetSheetDataResponse {
     pub values: Vec<Vec<Vec<String>>>, // One Vec<Vec<String>> per range
+    pub content_hashes: Vec<String>, // one per range
 }
 
+#[derive(serde::Deserialize)]
 pub struct BatchSetSheetDataRequest {
     pub spreadsheet_id: String,
-    pub updates: Vec<(String, Vec<Vec<String>>)>, // (range, values)
+    pub updates: Vec<(String, Vec<Vec<String>>)>, // (range or named range, values)
+    #[serde(default)]
+    pub override_protection: bool,
+    #[serde(default)]
+    pub expected: std::collections::HashMap<String, ExpectedContent>, // keyed by a range in `updates`
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct BatchSetSheetDataResponse {
     pub updated_cells: usize,
+    pub operation_id: Option<String>,
+    pub dry_run: Option<DryRunReport>,
 }
 
 pub struct BatchGetSheetDataHandler {
@@ -174,8 +233,15 @@ impl McpHandler for BatchGetSheetDataHandler {
     type Response = BatchGetSheetDataResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        self.client.batch_get_sheet_data(&req.spreadsheet_id, &req.ranges).await
-            .map(|values| BatchGetSheetDataResponse { values })
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        self.client.get_values_rendered(&req.spreadsheet_id, &req.ranges, "FORMATTED_VALUE").await
+            .map(|ranges| {
+                let values: Vec<_> = ranges.into_iter().map(|(_, v)| v).collect();
+                BatchGetSheetDataResponse {
+                    content_hashes: values.iter().map(|v| crate::diff::content_hash(v)).collect(),
+                    values,
+                }
+            })
             .map_err(SpreadsheetError::Api)
     }
 }
@@ -190,23 +256,43 @@ impl McpHandler for BatchSetSheetDataHandler {
     type Response = BatchSetSheetDataResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        self.client.batch_set_sheet_data(&req.spreadsheet_id, &req.updates).await
-            .map(|updated_cells| BatchSetSheetDataResponse { updated_cells })
-            .map_err(SpreadsheetError::Api)
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let writes: Vec<(&str, &Vec<Vec<String>>)> = req.updates.iter().map(|(r, v)| (r.as_str(), v)).collect();
+        if !req.override_protection {
+            ensure_unprotected(&self.client, &req.spreadsheet_id, &writes).await?;
+        }
+        for (range, expected) in &req.expected {
+            if !req.updates.iter().any(|(r, _)| r == range) {
+                return Err(SpreadsheetError::InvalidRequest(format!("Precondition for {} matches no update", range)));
+            }
+            check_expected(&self.client, &req.spreadsheet_id, range, expected).await?;
+        }
+        if req.dry_run {
+            let report = preview_writes(&self.client, &req.spreadsheet_id, &writes).await?;
+            return Ok(BatchSetSheetDataResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let backups = backup_ranges(&self.client, &req.spreadsheet_id, &writes).await?;
+        let updated_cells = self.client.batch_set_sheet_data(&req.spreadsheet_id, &req.updates).await
+            .map_err(SpreadsheetError::Api)?;
+        let operation_id = journal_write(&self.client, "batch_set_sheet_data", &req.spreadsheet_id, backups);
+        Ok(BatchSetSheetDataResponse { updated_cells, operation_id, dry_run: None })
     }
 }
 
 // Drive file operations
 pub struct ListDriveFilesRequest;
 
+#[derive(serde::Serialize, Debug)]
 pub struct ListDriveFilesResponse {
     pub files: Vec<String>,
 }
 
+#[derive(serde::Deserialize)]
 pub struct GetDriveFileMetadataRequest {
     pub file_id: String,
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct GetDriveFileMetadataResponse {
     pub name: String,
     pub mime_type: String,
@@ -239,6 +325,7 @@ impl McpHandler for GetDriveFileMetadataHandler {
     type Response = GetDriveFileMetadataResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.file_id).await.map_err(SpreadsheetError::Forbidden)?;
         self.client.get_drive_file_metadata(&req.file_id).await
             .map(|(name, mime_type, size)| GetDriveFileMetadataResponse { name, mime_type, size })
             .map_err(SpreadsheetError::Api)
@@ -247,17 +334,20 @@ impl McpHandler for GetDriveFileMetadataHandler {
 
 // ===== Sharing/Permissions Types =====
 
-#[derive(Debug)]
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
 pub enum ResourceType {
     Spreadsheet,
     DriveFile,
 }
 
+#[derive(serde::Deserialize)]
 pub struct ListPermissionsRequest {
     pub resource_id: String, // spreadsheet_id or file_id
     pub resource_type: ResourceType,
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct PermissionInfo {
     pub id: String,
     pub email: Option<String>,
@@ -265,30 +355,45 @@ pub struct PermissionInfo {
     pub type_: String, // e.g., user, group, domain, anyone
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct ListPermissionsResponse {
     pub permissions: Vec<PermissionInfo>,
 }
 
+#[derive(serde::Deserialize)]
 pub struct AddPermissionRequest {
     pub resource_id: String,
     pub resource_type: ResourceType,
     pub email: String,
     pub role: String, // e.g., reader, writer, owner
     pub type_: String, // e.g., user, group, domain, anyone
+    #[serde(default)]
+    pub send_notification: bool, // email the grantee about the share
+    #[serde(default)]
+    pub message: Option<String>, // included in the notification email
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct AddPermissionResponse {
     pub permission_id: String,
+    pub dry_run: Option<DryRunReport>,
 }
 
+#[derive(serde::Deserialize)]
 pub struct RemovePermissionRequest {
     pub resource_id: String,
     pub resource_type: ResourceType,
     pub permission_id: String,
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct RemovePermissionResponse {
     pub success: bool,
+    pub dry_run: Option<DryRunReport>,
 }
 
 pub struct ListPermissionsHandler {
@@ -301,14 +406,18 @@ impl McpHandler for ListPermissionsHandler {
     type Response = ListPermissionsResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.resource_id).await.map_err(SpreadsheetError::Forbidden)?;
         self.client.list_permissions(&req.resource_id, &req.resource_type).await
             .map(|permissions| ListPermissionsResponse { permissions })
             .map_err(SpreadsheetError::Api)
     }
 }
 
+/// Grants access after checking `AppConfig::sharing`. Every grant and refused attempt is
+/// written to the sharing audit log.
 pub struct AddPermissionHandler {
     pub client: Arc<GoogleApiClient>,
+    pub config: Arc<crate::config::AppConfig>,
 }
 
 #[async_trait]
@@ -317,14 +426,46 @@ impl McpHandler for AddPermissionHandler {
     type Response = AddPermissionResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        self.client.add_permission(&req.resource_id, &req.resource_type, &req.email, &req.role, &req.type_).await
-            .map(|permission_id| AddPermissionResponse { permission_id })
+        self.client.check_file_access(&req.resource_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let sharing = &self.config.sharing;
+        let audit = |permission_id: Option<&str>, outcome: String| crate::sharing::audit(sharing, &crate::sharing::AuditEntry {
+            timestamp: crate::sharing::now(),
+            action: "add_permission",
+            resource_id: &req.resource_id,
+            email: Some(req.email.as_str()),
+            role: Some(req.role.as_str()),
+            type_: Some(req.type_.as_str()),
+            permission_id,
+            outcome,
+        });
+        if let Err(reason) = crate::sharing::check_grant(sharing, &req.email, &req.role, &req.type_) {
+            if !req.dry_run {
+                audit(None, format!("denied: {}", reason));
+            }
+            return Err(SpreadsheetError::Forbidden(reason));
+        }
+        if req.dry_run {
+            let report = describe(
+                format!("Would grant {} access to {} ({}) on {}", req.role, req.email, req.type_, req.resource_id),
+                serde_json::json!({ "emailAddress": req.email, "role": req.role, "type": req.type_ }),
+            );
+            return Ok(AddPermissionResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let result = self.client.add_permission(&req.resource_id, &req.resource_type, &req.email, &req.role, &req.type_,
+            req.send_notification, req.message.as_deref()).await;
+        match &result {
+            Ok(permission_id) => audit(Some(permission_id.as_str()), "ok".to_string()),
+            Err(e) => audit(None, format!("error: {}", e)),
+        }
+        result
+            .map(|permission_id| AddPermissionResponse { permission_id, dry_run: None })
             .map_err(SpreadsheetError::Api)
     }
 }
 
 pub struct RemovePermissionHandler {
     pub client: Arc<GoogleApiClient>,
+    pub config: Arc<crate::config::AppConfig>,
 }
 
 #[async_trait]
@@ -333,43 +474,83 @@ impl McpHandler for RemovePermissionHandler {
     type Response = RemovePermissionResponse;
     type Error = SpreadsheetError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        self.client.remove_permission(&req.resource_id, &req.resource_type, &req.permission_id).await
-            .map(|success| RemovePermissionResponse { success })
+        self.client.check_file_access(&req.resource_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.dry_run {
+            let permissions = self.client.list_permissions(&req.resource_id, &req.resource_type).await
+                .map_err(SpreadsheetError::Api)?;
+            let permission = permissions.iter().find(|p| p.id == req.permission_id)
+                .ok_or_else(|| SpreadsheetError::InvalidRequest(format!("Permission not found: {}", req.permission_id)))?;
+            let report = describe(
+                format!("Would remove {} access for {} ({}) on {}",
+                    permission.role, permission.email.as_deref().unwrap_or("-"), permission.type_, req.resource_id),
+                serde_json::json!({ "permissionId": req.permission_id }),
+            );
+            return Ok(RemovePermissionResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let result = self.client.remove_permission(&req.resource_id, &req.resource_type, &req.permission_id).await;
+        crate::sharing::audit(&self.config.sharing, &crate::sharing::AuditEntry {
+            timestamp: crate::sharing::now(),
+            action: "remove_permission",
+            resource_id: &req.resource_id,
+            email: None,
+            role: None,
+            type_: None,
+            permission_id: Some(req.permission_id.as_str()),
+            outcome: match &result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => format!("error: {}", e),
+            },
+        });
+        result
+            .map(|success| RemovePermissionResponse { success, dry_run: None })
             .map_err(SpreadsheetError::Api)
     }
 }
 
 // ===== Google Docs Types =====
 
+#[derive(serde::Deserialize)]
 pub struct GetDocumentRequest {
     pub document_id: String,
 }
 
+#[derive(serde::Serialize, Debug)]
 pub struct GetDocumentResponse {
     pub title: String,
     pub body: String, // Simplified: full text content
 }
 
+#[derive(serde::Deserialize)]
 pub struct CreateDocumentRequest {
     pub title: String,
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct CreateDocumentResponse {
     pub document_id: String,
+    pub dry_run: Option<DryRunReport>,
 }
 
+#[derive(serde::Deserialize)]
 pub struct AppendTextRequest {
     pub document_id: String,
     pub text: String,
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
+#[derive(serde::Serialize, Debug, Default)]
 pub struct AppendTextResponse {
     pub success: bool,
+    pub dry_run: Option<DryRunReport>,
 }
 
 #[derive(Debug)]
 pub enum DocsError {
     Api(String),
+    Forbidden(String), // denied by the access policy
 }
 
 pub struct GetDocumentHandler {
@@ -391,6 +572,7 @@ impl McpHandler for GetDocumentHandler {
     type Error = DocsError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
         use google_docs1::api::Scope;
+        self.client.check_file_access(&req.document_id).await.map_err(DocsError::Forbidden)?;
         let docs = self.client.docs.lock().await;
         let result = docs.documents().get(&req.document_id).doit().await;
         match result {
@@ -426,13 +608,22 @@ impl McpHandler for CreateDocumentHandler {
     type Response = CreateDocumentResponse;
     type Error = DocsError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        let docs = self.client.docs.lock().await;
+        self.client.check_new_file(None).await.map_err(DocsError::Forbidden)?;
         let mut doc = google_docs1::api::Document::default();
         doc.title = Some(req.title);
+        if req.dry_run {
+            let report = describe(
+                format!("Would create document {:?}", doc.title.as_deref().unwrap_or_default()),
+                serde_json::to_value(&doc).unwrap_or_default(),
+            );
+            return Ok(CreateDocumentResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let docs = self.client.docs.lock().await;
         let result = docs.documents().create(doc).doit().await;
         match result {
             Ok((_, created)) => Ok(CreateDocumentResponse {
                 document_id: created.document_id.unwrap_or_default(),
+                dry_run: None,
             }),
             Err(e) => Err(DocsError::Api(format!("Docs API error: {e:?}"))),
         }
@@ -445,7 +636,8 @@ impl McpHandler for AppendTextHandler {
     type Response = AppendTextResponse;
     type Error = DocsError;
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
-        let docs = self.client.docs.lock().await;
+        self.client.check_file_access(&req.document_id).await.map_err(DocsError::Forbidden)?;
+        let length = req.text.chars().count();
         // Insert text at the end of the document
         let requests = vec![
             google_docs1::api::Request {
@@ -458,9 +650,17 @@ impl McpHandler for AppendTextHandler {
             }
         ];
         let batch_req = google_docs1::api::BatchUpdateDocumentRequest { requests: Some(requests), ..Default::default() };
+        if req.dry_run {
+            let report = describe(
+                format!("Would append {} characters to document {}", length, req.document_id),
+                serde_json::to_value(&batch_req).unwrap_or_default(),
+            );
+            return Ok(AppendTextResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let docs = self.client.docs.lock().await;
         let result = docs.documents().batch_update(batch_req, &req.document_id).doit().await;
         match result {
-            Ok(_) => Ok(AppendTextResponse { success: true }),
+            Ok(_) => Ok(AppendTextResponse { success: true, dry_run: None }),
             Err(e) => Err(DocsError::Api(format!("Docs API error: {e:?}"))),
         }
     }
@@ -470,12 +670,12 @@ impl McpHandler for AppendTextHandler {
 #[derive(serde::Deserialize)]
 pub struct ListDocsRequest {}
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug)]
 pub struct ListDocsResponse {
     pub docs: Vec<DocInfo>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug)]
 pub struct DocInfo {
     pub id: String,
     pub title: String,
@@ -508,7 +708,7 @@ pub struct GetCalendarEventRequest {
     pub event_id: String,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug)]
 pub struct GetCalendarEventResponse {
     pub id: String,
     pub summary: Option<String>,
@@ -524,11 +724,14 @@ pub struct CreateCalendarEventRequest {
     pub description: Option<String>,
     pub start: String, // RFC3339
     pub end: String,   // RFC3339
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug, Default)]
 pub struct CreateCalendarEventResponse {
     pub id: String,
+    pub dry_run: Option<DryRunReport>,
 }
 
 #[derive(serde::Deserialize)]
@@ -536,7 +739,7 @@ pub struct ListCalendarEventsRequest {
     pub calendar_id: String,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug)]
 pub struct CalendarEventInfo {
     pub id: String,
     pub summary: Option<String>,
@@ -544,7 +747,7 @@ pub struct CalendarEventInfo {
     pub end: Option<String>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Debug)]
 pub struct ListCalendarEventsResponse {
     pub events: Vec<CalendarEventInfo>,
 }
@@ -553,6 +756,7 @@ pub struct ListCalendarEventsResponse {
 #[derive(Debug)]
 pub enum CalendarError {
     Api(String),
+    Forbidden(String), // denied by the access policy
 }
 
 // Handler stubs
@@ -576,6 +780,7 @@ impl McpHandler for GetCalendarEventHandler {
 
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
         use google_calendar3::api::Scope;
+        self.client.check_calendar_access(&req.calendar_id).map_err(CalendarError::Forbidden)?;
         let calendar = self.client.calendar.lock().await;
         let (_, event) = calendar.events().get(&req.calendar_id, &req.event_id)
             .add_scope(Scope::Full)
@@ -598,6 +803,7 @@ impl McpHandler for CreateCalendarEventHandler {
 
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
         use google_calendar3::api::{Event, EventDateTime, Scope};
+        self.client.check_calendar_access(&req.calendar_id).map_err(CalendarError::Forbidden)?;
         let mut event = Event::default();
         event.summary = Some(req.summary);
         event.description = req.description;
@@ -610,12 +816,20 @@ impl McpHandler for CreateCalendarEventHandler {
             date_time: Some(req.end.parse::<chrono::DateTime<chrono::Utc>>().map_err(|e| CalendarError::Api(e.to_string()))?),
             ..Default::default()
         });
+        if req.dry_run {
+            let report = describe(
+                format!("Would create event {:?} in calendar {}", event.summary.as_deref().unwrap_or_default(), req.calendar_id),
+                serde_json::to_value(&event).unwrap_or_default(),
+            );
+            return Ok(CreateCalendarEventResponse { dry_run: Some(report), ..Default::default() });
+        }
         let calendar = self.client.calendar.lock().await;
         let (_, created) = calendar.events().insert(event, &req.calendar_id)
             .add_scope(Scope::Full)
             .doit().await.map_err(|e| CalendarError::Api(e.to_string()))?;
         Ok(CreateCalendarEventResponse {
             id: created.id.unwrap_or_default(),
+            dry_run: None,
         })
     }
 }
@@ -628,6 +842,7 @@ impl McpHandler for ListCalendarEventsHandler {
 
     async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
         use google_calendar3::api::Scope;
+        self.client.check_calendar_access(&req.calendar_id).map_err(CalendarError::Forbidden)?;
         let calendar = self.client.calendar.lock().await;
         let (_, events) = calendar.events().list(&req.calendar_id)
             .add_scope(Scope::Full)
@@ -643,6 +858,3770 @@ impl McpHandler for ListCalendarEventsHandler {
     }
 }
 
+// ===== Conditional Formatting Types =====
+
+/// Parses a `#RRGGBB` hex string into a Sheets color.
+pub fn parse_hex_color(hex: &str) -> Result<google_sheets4::api::Color, String> {
+    let digits = hex.trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid color: {}", hex));
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map(|v| v as f32 / 255.0).unwrap_or(0.0);
+    Ok(google_sheets4::api::Color {
+        red: Some(channel(0)),
+        green: Some(channel(2)),
+        blue: Some(channel(4)),
+        alpha: None,
+    })
+}
+
+/// Formats a Sheets color as a `#RRGGBB` hex string. Missing channels are 0.
+pub fn color_to_hex(color: &google_sheets4::api::Color) -> String {
+    let channel = |v: Option<f32>| (v.unwrap_or(0.0).clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02X}{:02X}{:02X}", channel(color.red), channel(color.green), channel(color.blue))
+}
+
+/// Format applied to cells matching a boolean or custom formula rule.
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+pub struct ConditionalCellFormat {
+    pub background_color: Option<String>, // "#RRGGBB"
+    pub text_color: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub strikethrough: Option<bool>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+pub struct GradientPoint {
+    pub color: String,
+    pub kind: String, // MIN, MAX, NUMBER, PERCENT, PERCENTILE
+    pub value: Option<String>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionalRule {
+    /// Built-in condition such as `NUMBER_GREATER`, `TEXT_CONTAINS` or `BLANK`.
+    Boolean { condition: String, values: Vec<String>, format: ConditionalCellFormat },
+    /// Formula evaluated relative to the top-left cell of the range, e.g. `=$C2="done"`.
+    CustomFormula { formula: String, format: ConditionalCellFormat },
+    Gradient { min: GradientPoint, mid: Option<GradientPoint>, max: GradientPoint },
+}
+
+impl ConditionalCellFormat {
+    fn to_api(&self) -> Result<google_sheets4::api::CellFormat, String> {
+        use google_sheets4::api::{CellFormat, TextFormat};
+        let text_format = TextFormat {
+            foreground_color: self.text_color.as_deref().map(parse_hex_color).transpose()?,
+            bold: self.bold,
+            italic: self.italic,
+            strikethrough: self.strikethrough,
+            ..Default::default()
+        };
+        Ok(CellFormat {
+            background_color: self.background_color.as_deref().map(parse_hex_color).transpose()?,
+            text_format: Some(text_format),
+            ..Default::default()
+        })
+    }
+
+    fn from_api(format: &google_sheets4::api::CellFormat) -> Self {
+        let text = format.text_format.as_ref();
+        ConditionalCellFormat {
+            background_color: format.background_color.as_ref().map(color_to_hex),
+            text_color: text.and_then(|t| t.foreground_color.as_ref()).map(color_to_hex),
+            bold: text.and_then(|t| t.bold),
+            italic: text.and_then(|t| t.italic),
+            strikethrough: text.and_then(|t| t.strikethrough),
+        }
+    }
+}
+
+impl GradientPoint {
+    fn to_api(&self) -> Result<google_sheets4::api::InterpolationPoint, String> {
+        Ok(google_sheets4::api::InterpolationPoint {
+            color: Some(parse_hex_color(&self.color)?),
+            type_: Some(self.kind.to_uppercase()),
+            value: self.value.clone(),
+            ..Default::default()
+        })
+    }
+
+    fn from_api(point: &google_sheets4::api::InterpolationPoint) -> Self {
+        GradientPoint {
+            color: point.color.as_ref().map(color_to_hex).unwrap_or_else(|| "#000000".to_string()),
+            kind: point.type_.clone().unwrap_or_default(),
+            value: point.value.clone(),
+        }
+    }
+}
+
+impl ConditionalRule {
+    pub fn to_api(&self, ranges: Vec<google_sheets4::api::GridRange>) -> Result<google_sheets4::api::ConditionalFormatRule, String> {
+        use google_sheets4::api::{BooleanCondition, BooleanRule, ConditionValue, ConditionalFormatRule, GradientRule};
+        let boolean_rule = |type_: String, values: Vec<String>, format: &ConditionalCellFormat| -> Result<BooleanRule, String> {
+            let values = values.into_iter()
+                .map(|v| ConditionValue { user_entered_value: Some(v), ..Default::default() })
+                .collect::<Vec<_>>();
+            Ok(BooleanRule {
+                condition: Some(BooleanCondition {
+                    type_: Some(type_),
+                    values: if values.is_empty() { None } else { Some(values) },
+                }),
+                format: Some(format.to_api()?),
+            })
+        };
+        let mut rule = ConditionalFormatRule { ranges: Some(ranges), ..Default::default() };
+        match self {
+            ConditionalRule::Boolean { condition, values, format } => {
+                rule.boolean_rule = Some(boolean_rule(condition.to_uppercase(), values.clone(), format)?);
+            }
+            ConditionalRule::CustomFormula { formula, format } => {
+                if !formula.starts_with('=') {
+                    return Err(format!("Custom formula must start with '=': {}", formula));
+                }
+                rule.boolean_rule = Some(boolean_rule("CUSTOM_FORMULA".to_string(), vec![formula.clone()], format)?);
+            }
+            ConditionalRule::Gradient { min, mid, max } => {
+                rule.gradient_rule = Some(GradientRule {
+                    minpoint: Some(min.to_api()?),
+                    midpoint: mid.as_ref().map(|m| m.to_api()).transpose()?,
+                    maxpoint: Some(max.to_api()?),
+                });
+            }
+        }
+        Ok(rule)
+    }
+
+    pub fn from_api(rule: &google_sheets4::api::ConditionalFormatRule) -> Option<Self> {
+        if let Some(boolean) = &rule.boolean_rule {
+            let condition = boolean.condition.as_ref()?;
+            let type_ = condition.type_.clone().unwrap_or_default();
+            let values: Vec<String> = condition.values.as_ref()
+                .map(|vs| vs.iter().filter_map(|v| v.user_entered_value.clone()).collect())
+                .unwrap_or_default();
+            let format = boolean.format.as_ref().map(ConditionalCellFormat::from_api).unwrap_or_default();
+            if type_ == "CUSTOM_FORMULA" {
+                return Some(ConditionalRule::CustomFormula { formula: values.into_iter().next().unwrap_or_default(), format });
+            }
+            return Some(ConditionalRule::Boolean { condition: type_, values, format });
+        }
+        let gradient = rule.gradient_rule.as_ref()?;
+        Some(ConditionalRule::Gradient {
+            min: GradientPoint::from_api(gradient.minpoint.as_ref()?),
+            mid: gradient.midpoint.as_ref().map(GradientPoint::from_api),
+            max: GradientPoint::from_api(gradient.maxpoint.as_ref()?),
+        })
+    }
+}
+
+/// Resolves ranges that must all live on one sheet, returning that sheet's ID and the grid ranges.
+async fn single_sheet_grid_ranges(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    ranges: &[A1Range],
+) -> Result<(i32, Vec<google_sheets4::api::GridRange>), SpreadsheetError> {
+    if ranges.is_empty() {
+        return Err(SpreadsheetError::InvalidRequest("At least one range is required".to_string()));
+    }
+    let mut grid_ranges = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        grid_ranges.push(client.grid_range(spreadsheet_id, range).await.map_err(SpreadsheetError::Api)?);
+    }
+    let sheet_id = grid_ranges[0].sheet_id.unwrap_or(0);
+    if grid_ranges.iter().any(|g| g.sheet_id.unwrap_or(0) != sheet_id) {
+        return Err(SpreadsheetError::InvalidRequest("All ranges must be on the same sheet".to_string()));
+    }
+    Ok((sheet_id, grid_ranges))
+}
+
+#[derive(serde::Deserialize)]
+pub struct ListConditionalFormatsRequest {
+    pub spreadsheet_id: String,
+    pub sheet: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct ConditionalFormatInfo {
+    pub index: usize, // position in the sheet's rule list; lower index = higher priority
+    pub ranges: Vec<String>,
+    pub rule: Option<ConditionalRule>, // None for rule kinds this server can't represent
+}
+
+#[derive(serde::Serialize)]
+pub struct ListConditionalFormatsResponse {
+    pub rules: Vec<ConditionalFormatInfo>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct AddConditionalFormatRequest {
+    pub spreadsheet_id: String,
+    pub ranges: Vec<A1Range>,
+    pub rule: ConditionalRule,
+    pub index: Option<i32>, // defaults to 0 (highest priority)
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct AddConditionalFormatResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+/// Replaces the rule at `index` when `rule` is set, and/or moves it to `new_index`.
+#[derive(serde::Deserialize)]
+pub struct UpdateConditionalFormatRequest {
+    pub spreadsheet_id: String,
+    pub sheet: String,
+    pub index: i32,
+    pub ranges: Vec<A1Range>,
+    pub rule: Option<ConditionalRule>,
+    pub new_index: Option<i32>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UpdateConditionalFormatResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteConditionalFormatRequest {
+    pub spreadsheet_id: String,
+    pub sheet: String,
+    pub index: i32,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct DeleteConditionalFormatResponse {
+    pub deleted: Option<ConditionalRule>,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct ListConditionalFormatsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ListConditionalFormatsHandler {
+    type Request = ListConditionalFormatsRequest;
+    type Response = ListConditionalFormatsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let rules = self.client.get_conditional_formats(&req.spreadsheet_id, &req.sheet).await
+            .map_err(SpreadsheetError::Api)?;
+        let rules = rules.iter().enumerate().map(|(index, rule)| ConditionalFormatInfo {
+            index,
+            ranges: rule.ranges.as_ref()
+                .map(|rs| rs.iter().map(|r| A1Range::from_grid_range(r, Some(&req.sheet)).to_string()).collect())
+                .unwrap_or_default(),
+            rule: ConditionalRule::from_api(rule),
+        }).collect();
+        Ok(ListConditionalFormatsResponse { rules })
+    }
+}
+
+pub struct AddConditionalFormatHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for AddConditionalFormatHandler {
+    type Request = AddConditionalFormatRequest;
+    type Response = AddConditionalFormatResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{AddConditionalFormatRuleRequest, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let (_, grid_ranges) = single_sheet_grid_ranges(&self.client, &req.spreadsheet_id, &req.ranges).await?;
+        let rule = req.rule.to_api(grid_ranges).map_err(SpreadsheetError::InvalidRequest)?;
+        let request = Request {
+            add_conditional_format_rule: Some(AddConditionalFormatRuleRequest {
+                index: Some(req.index.unwrap_or(0)),
+                rule: Some(rule),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(AddConditionalFormatResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| AddConditionalFormatResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+pub struct UpdateConditionalFormatHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UpdateConditionalFormatHandler {
+    type Request = UpdateConditionalFormatRequest;
+    type Response = UpdateConditionalFormatResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Request, UpdateConditionalFormatRuleRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let sheet_id = self.client.sheet_id(&req.spreadsheet_id, Some(&req.sheet)).await
+            .map_err(SpreadsheetError::Api)?;
+        let mut update = UpdateConditionalFormatRuleRequest {
+            index: Some(req.index),
+            sheet_id: Some(sheet_id),
+            ..Default::default()
+        };
+        match (req.rule, req.new_index) {
+            (Some(rule), None) => {
+                let (ranges_sheet_id, grid_ranges) = single_sheet_grid_ranges(&self.client, &req.spreadsheet_id, &req.ranges).await?;
+                if ranges_sheet_id != sheet_id {
+                    return Err(SpreadsheetError::InvalidRequest(format!("All ranges must be on sheet {}", req.sheet)));
+                }
+                update.rule = Some(rule.to_api(grid_ranges).map_err(SpreadsheetError::InvalidRequest)?);
+            }
+            (None, Some(new_index)) => update.new_index = Some(new_index),
+            _ => {
+                // The Sheets API takes either a replacement rule or a move, not both.
+                return Err(SpreadsheetError::InvalidRequest("Specify exactly one of rule or new_index".to_string()));
+            }
+        }
+        let request = Request { update_conditional_format_rule: Some(update), ..Default::default() };
+        if req.dry_run {
+            return Ok(UpdateConditionalFormatResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| UpdateConditionalFormatResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+pub struct DeleteConditionalFormatHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for DeleteConditionalFormatHandler {
+    type Request = DeleteConditionalFormatRequest;
+    type Response = DeleteConditionalFormatResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{DeleteConditionalFormatRuleRequest, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let sheet_id = self.client.sheet_id(&req.spreadsheet_id, Some(&req.sheet)).await
+            .map_err(SpreadsheetError::Api)?;
+        let request = Request {
+            delete_conditional_format_rule: Some(DeleteConditionalFormatRuleRequest {
+                index: Some(req.index),
+                sheet_id: Some(sheet_id),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(DeleteConditionalFormatResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        let resp = self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        let deleted = resp.replies.unwrap_or_default().into_iter().next()
+            .and_then(|r| r.delete_conditional_format_rule)
+            .and_then(|d| d.rule)
+            .and_then(|rule| ConditionalRule::from_api(&rule));
+        Ok(DeleteConditionalFormatResponse { deleted, dry_run: None })
+    }
+}
+
+// ===== Data Validation Types =====
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValidationRule {
+    /// Dropdown of fixed values.
+    List { values: Vec<String>, show_dropdown: Option<bool> },
+    /// Dropdown sourced from another range, e.g. `Lookups!A2:A20`.
+    Range { source: A1Range, show_dropdown: Option<bool> },
+    /// Checkbox; custom checked/unchecked values must be given together.
+    Checkbox { checked_value: Option<String>, unchecked_value: Option<String> },
+    /// `condition` is one of between, not_between, greater, greater_than_eq, less, less_than_eq, eq, not_eq.
+    Number { condition: String, values: Vec<String> },
+    /// `condition` is one of between, not_between, before, after, on_or_before, on_or_after, eq, is_valid.
+    Date { condition: String, values: Vec<String> },
+    CustomFormula { formula: String },
+}
+
+impl ValidationRule {
+    /// Whether the Sheets UI should render a dropdown/checkbox for this rule.
+    fn show_custom_ui(&self) -> Option<bool> {
+        match self {
+            ValidationRule::List { show_dropdown, .. } | ValidationRule::Range { show_dropdown, .. } => {
+                Some(show_dropdown.unwrap_or(true))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_condition(&self) -> Result<google_sheets4::api::BooleanCondition, String> {
+        use google_sheets4::api::{BooleanCondition, ConditionValue};
+        let (type_, values): (String, Vec<String>) = match self {
+            ValidationRule::List { values, .. } => {
+                if values.is_empty() {
+                    return Err("List validation requires at least one value".to_string());
+                }
+                ("ONE_OF_LIST".to_string(), values.clone())
+            }
+            ValidationRule::Range { source, .. } => ("ONE_OF_RANGE".to_string(), vec![format!("={}", source)]),
+            ValidationRule::Checkbox { checked_value, unchecked_value } => match (checked_value, unchecked_value) {
+                (None, None) => ("BOOLEAN".to_string(), vec![]),
+                (Some(checked), None) => ("BOOLEAN".to_string(), vec![checked.clone()]),
+                (Some(checked), Some(unchecked)) => ("BOOLEAN".to_string(), vec![checked.clone(), unchecked.clone()]),
+                (None, Some(_)) => return Err("unchecked_value requires checked_value".to_string()),
+            },
+            ValidationRule::Number { condition, values } => {
+                let condition = condition.to_uppercase();
+                let expected = match condition.as_str() {
+                    "BETWEEN" | "NOT_BETWEEN" => 2,
+                    "GREATER" | "GREATER_THAN_EQ" | "LESS" | "LESS_THAN_EQ" | "EQ" | "NOT_EQ" => 1,
+                    _ => return Err(format!("Unknown number condition: {}", condition)),
+                };
+                if values.len() != expected {
+                    return Err(format!("NUMBER_{} expects {} value(s), got {}", condition, expected, values.len()));
+                }
+                (format!("NUMBER_{}", condition), values.clone())
+            }
+            ValidationRule::Date { condition, values } => {
+                let condition = condition.to_uppercase();
+                let expected = match condition.as_str() {
+                    "BETWEEN" | "NOT_BETWEEN" => 2,
+                    "BEFORE" | "AFTER" | "ON_OR_BEFORE" | "ON_OR_AFTER" | "EQ" => 1,
+                    "IS_VALID" => 0,
+                    _ => return Err(format!("Unknown date condition: {}", condition)),
+                };
+                if values.len() != expected {
+                    return Err(format!("DATE_{} expects {} value(s), got {}", condition, expected, values.len()));
+                }
+                (format!("DATE_{}", condition), values.clone())
+            }
+            ValidationRule::CustomFormula { formula } => {
+                if !formula.starts_with('=') {
+                    return Err(format!("Custom formula must start with '=': {}", formula));
+                }
+                ("CUSTOM_FORMULA".to_string(), vec![formula.clone()])
+            }
+        };
+        let values: Vec<ConditionValue> = values.into_iter()
+            .map(|v| ConditionValue { user_entered_value: Some(v), ..Default::default() })
+            .collect();
+        Ok(BooleanCondition {
+            type_: Some(type_),
+            values: if values.is_empty() { None } else { Some(values) },
+        })
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct SetDataValidationRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range,
+    pub rule: ValidationRule,
+    pub strict: Option<bool>, // reject invalid input instead of showing a warning; default true
+    pub input_message: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SetDataValidationResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ClearDataValidationRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct ClearDataValidationResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct SetDataValidationHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SetDataValidationHandler {
+    type Request = SetDataValidationRequest;
+    type Response = SetDataValidationResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{DataValidationRule, Request, SetDataValidationRequest as ApiSetDataValidationRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let condition = req.rule.to_condition().map_err(SpreadsheetError::InvalidRequest)?;
+        let range = self.client.grid_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let rule = DataValidationRule {
+            condition: Some(condition),
+            input_message: req.input_message,
+            show_custom_ui: req.rule.show_custom_ui(),
+            strict: Some(req.strict.unwrap_or(true)),
+        };
+        let request = Request {
+            set_data_validation: Some(ApiSetDataValidationRequest { range: Some(range), rule: Some(rule) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(SetDataValidationResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| SetDataValidationResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+pub struct ClearDataValidationHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ClearDataValidationHandler {
+    type Request = ClearDataValidationRequest;
+    type Response = ClearDataValidationResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Request, SetDataValidationRequest as ApiSetDataValidationRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let range = self.client.grid_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        // A setDataValidation request without a rule removes validation from the range.
+        let request = Request {
+            set_data_validation: Some(ApiSetDataValidationRequest { range: Some(range), rule: None }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(ClearDataValidationResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| ClearDataValidationResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+// ===== Named Range Types =====
+
+/// Checks a name against the Sheets rules for named ranges: letters, digits and
+/// underscores, not starting with a digit, and not readable as a cell reference.
+pub fn validate_named_range_name(name: &str) -> Result<(), String> {
+    let valid_chars = name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    let starts_ok = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_');
+    if name.is_empty() || name.len() > 250 || !valid_chars || !starts_ok {
+        return Err(format!("Invalid named range name: {}", name));
+    }
+    if name.eq_ignore_ascii_case("true") || name.eq_ignore_ascii_case("false") {
+        return Err(format!("Invalid named range name: {}", name));
+    }
+    if A1Range::parse(name).map(|r| !r.is_bare_name()).unwrap_or(false) {
+        return Err(format!("Named range name looks like a cell reference: {}", name));
+    }
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+pub struct ListNamedRangesRequest {
+    pub spreadsheet_id: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct NamedRangeInfo {
+    pub id: String,
+    pub name: String,
+    pub range: String, // A1 notation
+}
+
+#[derive(serde::Serialize)]
+pub struct ListNamedRangesResponse {
+    pub named_ranges: Vec<NamedRangeInfo>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateNamedRangeRequest {
+    pub spreadsheet_id: String,
+    pub name: String,
+    pub range: A1Range,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct CreateNamedRangeResponse {
+    pub named_range_id: String,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdateNamedRangeRequest {
+    pub spreadsheet_id: String,
+    pub named_range_id: String,
+    pub name: Option<String>,
+    pub range: Option<A1Range>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UpdateNamedRangeResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteNamedRangeRequest {
+    pub spreadsheet_id: String,
+    pub named_range_id: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct DeleteNamedRangeResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct ListNamedRangesHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ListNamedRangesHandler {
+    type Request = ListNamedRangesRequest;
+    type Response = ListNamedRangesResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let named = self.client.list_named_ranges(&req.spreadsheet_id).await
+            .map_err(SpreadsheetError::Api)?;
+        let named_ranges = named.into_iter().map(|(n, sheet_title)| NamedRangeInfo {
+            id: n.named_range_id.unwrap_or_default(),
+            name: n.name.unwrap_or_default(),
+            range: n.range.as_ref()
+                .map(|r| A1Range::from_grid_range(r, sheet_title.as_deref()).to_string())
+                .unwrap_or_default(),
+        }).collect();
+        Ok(ListNamedRangesResponse { named_ranges })
+    }
+}
+
+pub struct CreateNamedRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for CreateNamedRangeHandler {
+    type Request = CreateNamedRangeRequest;
+    type Response = CreateNamedRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{AddNamedRangeRequest, NamedRange, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        validate_named_range_name(&req.name).map_err(SpreadsheetError::InvalidRequest)?;
+        let range = self.client.grid_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let request = Request {
+            add_named_range: Some(AddNamedRangeRequest {
+                named_range: Some(NamedRange { name: Some(req.name), range: Some(range), named_range_id: None }),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(CreateNamedRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        let resp = self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        let named_range_id = resp.replies.unwrap_or_default().into_iter().next()
+            .and_then(|r| r.add_named_range)
+            .and_then(|a| a.named_range)
+            .and_then(|n| n.named_range_id)
+            .unwrap_or_default();
+        Ok(CreateNamedRangeResponse { named_range_id, dry_run: None })
+    }
+}
+
+pub struct UpdateNamedRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UpdateNamedRangeHandler {
+    type Request = UpdateNamedRangeRequest;
+    type Response = UpdateNamedRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{NamedRange, Request, UpdateNamedRangeRequest as ApiUpdateNamedRangeRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let mut fields = Vec::new();
+        let mut named_range = NamedRange { named_range_id: Some(req.named_range_id), ..Default::default() };
+        if let Some(name) = req.name {
+            validate_named_range_name(&name).map_err(SpreadsheetError::InvalidRequest)?;
+            named_range.name = Some(name);
+            fields.push("name");
+        }
+        if let Some(range) = &req.range {
+            named_range.range = Some(self.client.grid_range(&req.spreadsheet_id, range).await.map_err(SpreadsheetError::Api)?);
+            fields.push("range");
+        }
+        if fields.is_empty() {
+            return Err(SpreadsheetError::InvalidRequest("Nothing to update: set name and/or range".to_string()));
+        }
+        let request = Request {
+            update_named_range: Some(ApiUpdateNamedRangeRequest {
+                fields: Some(google_sheets4::FieldMask::new(&fields)),
+                named_range: Some(named_range),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(UpdateNamedRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| UpdateNamedRangeResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+pub struct DeleteNamedRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for DeleteNamedRangeHandler {
+    type Request = DeleteNamedRangeRequest;
+    type Response = DeleteNamedRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{DeleteNamedRangeRequest as ApiDeleteNamedRangeRequest, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let request = Request {
+            delete_named_range: Some(ApiDeleteNamedRangeRequest { named_range_id: Some(req.named_range_id) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(DeleteNamedRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| DeleteNamedRangeResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+// ===== Protected Range Types =====
+
+/// Fields of `spreadsheets.get` that `check_protection` needs: protected ranges, plus what
+/// `resolve_in` uses to resolve the written ranges.
+pub const PROTECTION_FIELDS: &str = "sheets(properties(sheetId,title),protectedRanges),namedRanges(name,range)";
+
+/// Rejects writes whose cells overlap a protected range; see `check_protection`. Fetches
+/// the spreadsheet metadata once for all of `writes`.
+async fn ensure_unprotected(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    writes: &[(&str, &Vec<Vec<String>>)],
+) -> Result<(), SpreadsheetError> {
+    let spreadsheet = client.get_spreadsheet(spreadsheet_id, PROTECTION_FIELDS).await.map_err(SpreadsheetError::Api)?;
+    let mut cells = Vec::with_capacity(writes.len());
+    for (range, values) in writes {
+        let grid = resolve_grid(&spreadsheet, range)?;
+        cells.push((range.to_string(), crate::a1::written_extent(&grid, values)));
+    }
+    check_protection(&spreadsheet, &cells)
+}
+
+/// Resolves an A1 range (or named range) against metadata fetched with `PROTECTION_FIELDS`.
+fn resolve_grid(spreadsheet: &google_sheets4::api::Spreadsheet, range: &str) -> Result<google_sheets4::api::GridRange, SpreadsheetError> {
+    let a1 = A1Range::parse(range).map_err(SpreadsheetError::InvalidRequest)?;
+    crate::google_api_client::resolve_in(spreadsheet, &a1)
+        .map(|(grid, _)| grid)
+        .map_err(SpreadsheetError::Api)
+}
+
+/// Rejects changes to `cells` (a label for the error message and the cells touched) that
+/// overlap a protected range, including warning-only ones, unless they fall entirely
+/// inside one of that range's unprotected exceptions.
+pub fn check_protection(
+    spreadsheet: &google_sheets4::api::Spreadsheet,
+    cells: &[(String, google_sheets4::api::GridRange)],
+) -> Result<(), SpreadsheetError> {
+    let protected: Vec<_> = spreadsheet.sheets.iter().flatten().flat_map(|sheet| {
+        let title = sheet.properties.as_ref().and_then(|p| p.title.as_deref());
+        sheet.protected_ranges.iter().flatten().map(move |p| (p, title))
+    }).collect();
+    for (label, written) in cells {
+        for (p, sheet_title) in &protected {
+            let Some(protected_grid) = &p.range else { continue };
+            if !crate::a1::grid_ranges_overlap(written, protected_grid) {
+                continue;
+            }
+            let exempt = p.unprotected_ranges.as_ref()
+                .is_some_and(|us| us.iter().any(|u| crate::a1::grid_range_contains(u, written)));
+            if exempt {
+                continue;
+            }
+            return Err(SpreadsheetError::Protected(format!(
+                "Write to {} overlaps protected range {} ({}{}); set override_protection to write anyway",
+                label,
+                p.protected_range_id.unwrap_or_default(),
+                A1Range::from_grid_range(protected_grid, *sheet_title),
+                if p.warning_only.unwrap_or(false) { ", warning only" } else { "" },
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+pub struct ListProtectedRangesRequest {
+    pub spreadsheet_id: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct ProtectedRangeInfo {
+    pub id: i32,
+    pub range: String, // A1 notation
+    pub description: Option<String>,
+    pub warning_only: bool,
+    pub editors: Vec<String>, // user emails
+    pub groups: Vec<String>,
+    pub unprotected_ranges: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ListProtectedRangesResponse {
+    pub protected_ranges: Vec<ProtectedRangeInfo>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct AddProtectedRangeRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub warning_only: bool, // show a warning instead of blocking edits; cannot have editors
+    #[serde(default)]
+    pub editors: Vec<String>,
+    #[serde(default)]
+    pub unprotected_ranges: Vec<A1Range>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct AddProtectedRangeResponse {
+    pub protected_range_id: i32,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdateProtectedRangeRequest {
+    pub spreadsheet_id: String,
+    pub protected_range_id: i32,
+    pub range: Option<A1Range>,
+    pub description: Option<String>,
+    pub warning_only: Option<bool>,
+    pub editors: Option<Vec<String>>, // replaces the full editor list
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UpdateProtectedRangeResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct RemoveProtectedRangeRequest {
+    pub spreadsheet_id: String,
+    pub protected_range_id: i32,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct RemoveProtectedRangeResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct ListProtectedRangesHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ListProtectedRangesHandler {
+    type Request = ListProtectedRangesRequest;
+    type Response = ListProtectedRangesResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let protected = self.client.list_protected_ranges(&req.spreadsheet_id).await
+            .map_err(SpreadsheetError::Api)?;
+        let protected_ranges = protected.into_iter().map(|(p, sheet_title)| {
+            let to_a1 = |g: &google_sheets4::api::GridRange| A1Range::from_grid_range(g, sheet_title.as_deref()).to_string();
+            let editors = p.editors.unwrap_or_default();
+            ProtectedRangeInfo {
+                id: p.protected_range_id.unwrap_or_default(),
+                range: p.range.as_ref().map(to_a1).unwrap_or_default(),
+                description: p.description,
+                warning_only: p.warning_only.unwrap_or(false),
+                editors: editors.users.unwrap_or_default(),
+                groups: editors.groups.unwrap_or_default(),
+                unprotected_ranges: p.unprotected_ranges.unwrap_or_default().iter().map(to_a1).collect(),
+            }
+        }).collect();
+        Ok(ListProtectedRangesResponse { protected_ranges })
+    }
+}
+
+pub struct AddProtectedRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for AddProtectedRangeHandler {
+    type Request = AddProtectedRangeRequest;
+    type Response = AddProtectedRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{AddProtectedRangeRequest as ApiAddProtectedRangeRequest, Editors, ProtectedRange, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.warning_only && !req.editors.is_empty() {
+            return Err(SpreadsheetError::InvalidRequest("Warning-only protected ranges cannot have editors".to_string()));
+        }
+        let range = self.client.grid_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let mut unprotected_ranges = Vec::with_capacity(req.unprotected_ranges.len());
+        for r in &req.unprotected_ranges {
+            unprotected_ranges.push(self.client.grid_range(&req.spreadsheet_id, r).await.map_err(SpreadsheetError::Api)?);
+        }
+        let protected_range = ProtectedRange {
+            range: Some(range),
+            description: req.description,
+            warning_only: Some(req.warning_only),
+            editors: if req.warning_only { None } else { Some(Editors { users: Some(req.editors), ..Default::default() }) },
+            unprotected_ranges: if unprotected_ranges.is_empty() { None } else { Some(unprotected_ranges) },
+            ..Default::default()
+        };
+        let request = Request {
+            add_protected_range: Some(ApiAddProtectedRangeRequest { protected_range: Some(protected_range) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(AddProtectedRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        let resp = self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        let protected_range_id = resp.replies.unwrap_or_default().into_iter().next()
+            .and_then(|r| r.add_protected_range)
+            .and_then(|a| a.protected_range)
+            .and_then(|p| p.protected_range_id)
+            .unwrap_or_default();
+        Ok(AddProtectedRangeResponse { protected_range_id, dry_run: None })
+    }
+}
+
+pub struct UpdateProtectedRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UpdateProtectedRangeHandler {
+    type Request = UpdateProtectedRangeRequest;
+    type Response = UpdateProtectedRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Editors, ProtectedRange, Request, UpdateProtectedRangeRequest as ApiUpdateProtectedRangeRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.warning_only == Some(true) && req.editors.as_ref().is_some_and(|e| !e.is_empty()) {
+            return Err(SpreadsheetError::InvalidRequest("Warning-only protected ranges cannot have editors".to_string()));
+        }
+        let mut fields = Vec::new();
+        let mut protected_range = ProtectedRange { protected_range_id: Some(req.protected_range_id), ..Default::default() };
+        if let Some(range) = &req.range {
+            protected_range.range = Some(self.client.grid_range(&req.spreadsheet_id, range).await.map_err(SpreadsheetError::Api)?);
+            fields.push("range");
+        }
+        if let Some(description) = req.description {
+            protected_range.description = Some(description);
+            fields.push("description");
+        }
+        if let Some(warning_only) = req.warning_only {
+            protected_range.warning_only = Some(warning_only);
+            fields.push("warningOnly");
+        }
+        if let Some(editors) = req.editors {
+            protected_range.editors = Some(Editors { users: Some(editors), ..Default::default() });
+            fields.push("editors");
+        }
+        if fields.is_empty() {
+            return Err(SpreadsheetError::InvalidRequest("Nothing to update".to_string()));
+        }
+        let request = Request {
+            update_protected_range: Some(ApiUpdateProtectedRangeRequest {
+                fields: Some(google_sheets4::FieldMask::new(&fields)),
+                protected_range: Some(protected_range),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(UpdateProtectedRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| UpdateProtectedRangeResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+pub struct RemoveProtectedRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for RemoveProtectedRangeHandler {
+    type Request = RemoveProtectedRangeRequest;
+    type Response = RemoveProtectedRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{DeleteProtectedRangeRequest, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let request = Request {
+            delete_protected_range: Some(DeleteProtectedRangeRequest { protected_range_id: Some(req.protected_range_id) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(RemoveProtectedRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| RemoveProtectedRangeResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+// ===== Chart Types =====
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartType {
+    Line,
+    Bar, // horizontal bars
+    Column,
+    Pie,
+    Scatter,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct ChartSeriesOptions {
+    pub range: A1Range, // a single column of values
+    pub target_axis: Option<String>, // LEFT_AXIS (default), RIGHT_AXIS; BOTTOM_AXIS for bar charts
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct ChartOptions {
+    pub chart_type: ChartType,
+    /// The first column is the domain (labels / x values); the remaining columns are
+    /// series unless `series` is given.
+    pub source: A1Range,
+    pub title: Option<String>,
+    pub header_rows: Option<i32>, // default 1
+    pub x_axis_title: Option<String>,
+    pub y_axis_title: Option<String>,
+    pub legend_position: Option<String>, // BOTTOM_LEGEND, RIGHT_LEGEND, NO_LEGEND, ...
+    pub series: Option<Vec<ChartSeriesOptions>>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChartPlacement {
+    /// Floating over the sheet with its top-left corner at `anchor`.
+    Overlay { anchor: A1Range, width_pixels: Option<i32>, height_pixels: Option<i32> },
+    /// On a new sheet of its own.
+    NewSheet,
+}
+
+/// Splits a column-bounded grid range into one grid range per column.
+pub fn split_grid_columns(grid: &google_sheets4::api::GridRange) -> Result<Vec<google_sheets4::api::GridRange>, String> {
+    let (Some(start), Some(end)) = (grid.start_column_index, grid.end_column_index) else {
+        return Err("Chart source must have explicit column bounds".to_string());
+    };
+    Ok((start..end).map(|col| google_sheets4::api::GridRange {
+        start_column_index: Some(col),
+        end_column_index: Some(col + 1),
+        ..grid.clone()
+    }).collect())
+}
+
+impl ChartOptions {
+    /// Builds the chart spec from an already-resolved domain column and series ranges
+    /// (paired with an optional target axis).
+    pub fn to_spec(
+        &self,
+        domain: google_sheets4::api::GridRange,
+        series: Vec<(google_sheets4::api::GridRange, Option<String>)>,
+    ) -> Result<google_sheets4::api::ChartSpec, String> {
+        use google_sheets4::api::{
+            BasicChartAxis, BasicChartDomain, BasicChartSeries, BasicChartSpec, ChartData, ChartSourceRange, ChartSpec, PieChartSpec,
+        };
+        if series.is_empty() {
+            return Err("Chart needs at least one series column".to_string());
+        }
+        let data = |range: google_sheets4::api::GridRange| ChartData {
+            source_range: Some(ChartSourceRange { sources: Some(vec![range]) }),
+            ..Default::default()
+        };
+        let mut spec = ChartSpec { title: self.title.clone(), ..Default::default() };
+        if self.chart_type == ChartType::Pie {
+            if series.len() != 1 {
+                return Err("Pie charts take exactly one series".to_string());
+            }
+            let (values, _) = series.into_iter().next().unwrap();
+            spec.pie_chart = Some(PieChartSpec {
+                domain: Some(data(domain)),
+                series: Some(data(values)),
+                legend_position: Some(self.legend_position.clone().unwrap_or_else(|| "RIGHT_LEGEND".to_string())),
+                ..Default::default()
+            });
+            return Ok(spec);
+        }
+        let (chart_type, domain_axis, value_axis) = match self.chart_type {
+            ChartType::Line => ("LINE", "BOTTOM_AXIS", "LEFT_AXIS"),
+            ChartType::Bar => ("BAR", "LEFT_AXIS", "BOTTOM_AXIS"),
+            ChartType::Column => ("COLUMN", "BOTTOM_AXIS", "LEFT_AXIS"),
+            ChartType::Scatter => ("SCATTER", "BOTTOM_AXIS", "LEFT_AXIS"),
+            ChartType::Pie => unreachable!(),
+        };
+        let axis = |position: &str, title: &Option<String>| BasicChartAxis {
+            position: Some(position.to_string()),
+            title: title.clone(),
+            ..Default::default()
+        };
+        spec.basic_chart = Some(BasicChartSpec {
+            chart_type: Some(chart_type.to_string()),
+            legend_position: Some(self.legend_position.clone().unwrap_or_else(|| "BOTTOM_LEGEND".to_string())),
+            header_count: Some(self.header_rows.unwrap_or(1)),
+            axis: Some(vec![axis(domain_axis, &self.x_axis_title), axis(value_axis, &self.y_axis_title)]),
+            domains: Some(vec![BasicChartDomain { domain: Some(data(domain)), ..Default::default() }]),
+            series: Some(series.into_iter().map(|(range, target_axis)| BasicChartSeries {
+                series: Some(data(range)),
+                target_axis: Some(target_axis.unwrap_or_else(|| value_axis.to_string())),
+                ..Default::default()
+            }).collect()),
+            ..Default::default()
+        });
+        Ok(spec)
+    }
+}
+
+/// Resolves a chart's source and series ranges and builds its spec.
+async fn build_chart_spec(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    options: &ChartOptions,
+) -> Result<google_sheets4::api::ChartSpec, SpreadsheetError> {
+    let source = client.grid_range(spreadsheet_id, &options.source).await.map_err(SpreadsheetError::Api)?;
+    let mut columns = split_grid_columns(&source).map_err(SpreadsheetError::InvalidRequest)?.into_iter();
+    let domain = columns.next().ok_or_else(|| SpreadsheetError::InvalidRequest("Chart source is empty".to_string()))?;
+    let series = match &options.series {
+        Some(series) => {
+            let mut resolved = Vec::with_capacity(series.len());
+            for s in series {
+                let grid = client.grid_range(spreadsheet_id, &s.range).await.map_err(SpreadsheetError::Api)?;
+                resolved.push((grid, s.target_axis.clone()));
+            }
+            resolved
+        }
+        None => columns.map(|c| (c, None)).collect(),
+    };
+    options.to_spec(domain, series).map_err(SpreadsheetError::InvalidRequest)
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateChartRequest {
+    pub spreadsheet_id: String,
+    pub chart: ChartOptions,
+    pub placement: Option<ChartPlacement>, // default: overlay to the right of the source data
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct CreateChartResponse {
+    pub chart_id: i32,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdateChartRequest {
+    pub spreadsheet_id: String,
+    pub chart_id: i32,
+    pub chart: ChartOptions, // replaces the whole spec
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UpdateChartResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteChartRequest {
+    pub spreadsheet_id: String,
+    pub chart_id: i32,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct DeleteChartResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct CreateChartHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for CreateChartHandler {
+    type Request = CreateChartRequest;
+    type Response = CreateChartResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{AddChartRequest, EmbeddedChart, EmbeddedObjectPosition, GridCoordinate, OverlayPosition, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let spec = build_chart_spec(&self.client, &req.spreadsheet_id, &req.chart).await?;
+        let position = match req.placement {
+            Some(ChartPlacement::NewSheet) => EmbeddedObjectPosition { new_sheet: Some(true), ..Default::default() },
+            placement => {
+                let (anchor, width_pixels, height_pixels) = match placement {
+                    Some(ChartPlacement::Overlay { anchor, width_pixels, height_pixels }) => {
+                        (self.client.grid_range(&req.spreadsheet_id, &anchor).await.map_err(SpreadsheetError::Api)?, width_pixels, height_pixels)
+                    }
+                    _ => {
+                        let source = self.client.grid_range(&req.spreadsheet_id, &req.chart.source).await.map_err(SpreadsheetError::Api)?;
+                        let anchor = google_sheets4::api::GridRange {
+                            start_column_index: Some(source.end_column_index.unwrap_or(0) + 1),
+                            ..source
+                        };
+                        (anchor, None, None)
+                    }
+                };
+                EmbeddedObjectPosition {
+                    overlay_position: Some(OverlayPosition {
+                        anchor_cell: Some(GridCoordinate {
+                            sheet_id: anchor.sheet_id,
+                            row_index: Some(anchor.start_row_index.unwrap_or(0)),
+                            column_index: Some(anchor.start_column_index.unwrap_or(0)),
+                        }),
+                        width_pixels,
+                        height_pixels,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            }
+        };
+        let request = Request {
+            add_chart: Some(AddChartRequest {
+                chart: Some(EmbeddedChart { spec: Some(spec), position: Some(position), ..Default::default() }),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(CreateChartResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        let resp = self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        let chart_id = resp.replies.unwrap_or_default().into_iter().next()
+            .and_then(|r| r.add_chart)
+            .and_then(|a| a.chart)
+            .and_then(|c| c.chart_id)
+            .ok_or_else(|| SpreadsheetError::Api("addChart reply did not include a chart ID".to_string()))?;
+        Ok(CreateChartResponse { chart_id, dry_run: None })
+    }
+}
+
+pub struct UpdateChartHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UpdateChartHandler {
+    type Request = UpdateChartRequest;
+    type Response = UpdateChartResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Request, UpdateChartSpecRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let spec = build_chart_spec(&self.client, &req.spreadsheet_id, &req.chart).await?;
+        let request = Request {
+            update_chart_spec: Some(UpdateChartSpecRequest { chart_id: Some(req.chart_id), spec: Some(spec) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(UpdateChartResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| UpdateChartResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+pub struct DeleteChartHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for DeleteChartHandler {
+    type Request = DeleteChartRequest;
+    type Response = DeleteChartResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{DeleteEmbeddedObjectRequest, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let request = Request {
+            delete_embedded_object: Some(DeleteEmbeddedObjectRequest { object_id: Some(req.chart_id) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(DeleteChartResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map(|_| DeleteChartResponse { success: true, dry_run: None })
+            .map_err(SpreadsheetError::Api)
+    }
+}
+
+// ===== Raw batchUpdate Types =====
+
+/// IDs that raw requests may reference, as they exist before the batch runs.
+#[derive(Default)]
+pub struct SpreadsheetStructure {
+    pub sheet_ids: std::collections::HashSet<i32>,
+    pub named_range_ids: std::collections::HashSet<String>,
+    pub protected_range_ids: std::collections::HashSet<i32>,
+}
+
+impl SpreadsheetStructure {
+    pub fn from_spreadsheet(spreadsheet: &google_sheets4::api::Spreadsheet) -> Self {
+        let mut structure = SpreadsheetStructure::default();
+        for sheet in spreadsheet.sheets.iter().flatten() {
+            if let Some(id) = sheet.properties.as_ref().and_then(|p| p.sheet_id) {
+                structure.sheet_ids.insert(id);
+            }
+            for p in sheet.protected_ranges.iter().flatten() {
+                if let Some(id) = p.protected_range_id {
+                    structure.protected_range_ids.insert(id);
+                }
+            }
+        }
+        for n in spreadsheet.named_ranges.iter().flatten() {
+            if let Some(id) = &n.named_range_id {
+                structure.named_range_ids.insert(id.clone());
+            }
+        }
+        structure
+    }
+}
+
+/// Checks that each request sets exactly one operation and that every sheet, named range
+/// and protected range it references exists (or is created earlier in the same batch).
+pub fn validate_raw_requests(requests: &[google_sheets4::api::Request], structure: &SpreadsheetStructure) -> Result<(), String> {
+    fn referenced_sheet_ids(value: &serde_json::Value, out: &mut Vec<i64>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, v) in map {
+                    if key == "sheetId" || key == "sourceSheetId" {
+                        if let Some(id) = v.as_i64() {
+                            out.push(id);
+                        }
+                    } else {
+                        referenced_sheet_ids(v, out);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| referenced_sheet_ids(v, out)),
+            _ => {}
+        }
+    }
+
+    let mut sheet_ids: std::collections::HashSet<i64> = structure.sheet_ids.iter().map(|&id| id as i64).collect();
+    let mut named_range_ids = structure.named_range_ids.clone();
+    for (i, request) in requests.iter().enumerate() {
+        let value = serde_json::to_value(request).map(strip_nulls).map_err(|e| format!("Request {}: {}", i, e))?;
+        let ops = value.as_object().map(|o| o.len()).unwrap_or(0);
+        if ops != 1 {
+            return Err(format!("Request {} must set exactly one operation, found {}", i, ops));
+        }
+        let (kind, body) = value.as_object().and_then(|o| o.iter().next()).unwrap();
+        match kind.as_str() {
+            "addSheet" => {
+                // A new sheet may pick its own ID; later requests in the batch can then use it.
+                if let Some(id) = body.pointer("/properties/sheetId").and_then(|v| v.as_i64()) {
+                    if !sheet_ids.insert(id) {
+                        return Err(format!("Request {} (addSheet) reuses existing sheetId {}", i, id));
+                    }
+                }
+                continue;
+            }
+            "addNamedRange" => {
+                if let Some(id) = body.pointer("/namedRange/namedRangeId").and_then(|v| v.as_str()) {
+                    named_range_ids.insert(id.to_string());
+                }
+            }
+            "updateNamedRange" | "deleteNamedRange" => {
+                let id = body.pointer("/namedRangeId").or_else(|| body.pointer("/namedRange/namedRangeId"))
+                    .and_then(|v| v.as_str());
+                match id {
+                    Some(id) if named_range_ids.contains(id) => {}
+                    Some(id) => return Err(format!("Request {} ({}) references unknown namedRangeId {}", i, kind, id)),
+                    None => return Err(format!("Request {} ({}) is missing namedRangeId", i, kind)),
+                }
+            }
+            "updateProtectedRange" | "deleteProtectedRange" => {
+                let id = body.pointer("/protectedRangeId").or_else(|| body.pointer("/protectedRange/protectedRangeId"))
+                    .and_then(|v| v.as_i64());
+                match id {
+                    Some(id) if structure.protected_range_ids.contains(&(id as i32)) => {}
+                    Some(id) => return Err(format!("Request {} ({}) references unknown protectedRangeId {}", i, kind, id)),
+                    None => return Err(format!("Request {} ({}) is missing protectedRangeId", i, kind)),
+                }
+            }
+            _ => {}
+        }
+        let mut referenced = Vec::new();
+        referenced_sheet_ids(body, &mut referenced);
+        if let Some(id) = referenced.iter().find(|id| !sheet_ids.contains(id)) {
+            return Err(format!("Request {} ({}) references unknown sheetId {}", i, kind, id));
+        }
+        if kind == "duplicateSheet" {
+            if let Some(id) = body.pointer("/newSheetId").and_then(|v| v.as_i64()) {
+                sheet_ids.insert(id);
+            }
+        }
+        if kind == "deleteSheet" {
+            for id in referenced {
+                sheet_ids.remove(&id);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+pub struct SheetsBatchUpdateRequest {
+    pub spreadsheet_id: String,
+    pub requests: Vec<google_sheets4::api::Request>, // raw Sheets API `Request` objects
+    #[serde(default)]
+    pub include_spreadsheet_in_response: bool,
+    #[serde(default)]
+    pub override_protection: bool, // send requests that touch protected ranges anyway
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SheetsBatchUpdateResponse {
+    pub replies: Vec<google_sheets4::api::Response>,
+    pub spreadsheet: Option<google_sheets4::api::Spreadsheet>,
+    pub dry_run: Option<DryRunReport>,
+}
+
+/// Only available when `features.raw_batch_update` is enabled in the config.
+pub struct SheetsBatchUpdateHandler {
+    pub client: Arc<GoogleApiClient>,
+    pub config: Arc<crate::config::AppConfig>,
+}
+
+#[async_trait]
+impl McpHandler for SheetsBatchUpdateHandler {
+    type Request = SheetsBatchUpdateRequest;
+    type Response = SheetsBatchUpdateResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        if !self.config.features.raw_batch_update {
+            return Err(SpreadsheetError::Forbidden("sheets_batch_update is disabled; set features.raw_batch_update".to_string()));
+        }
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.requests.is_empty() {
+            return Err(SpreadsheetError::InvalidRequest("No requests given".to_string()));
+        }
+        let spreadsheet = self.client.get_spreadsheet(
+            &req.spreadsheet_id,
+            "sheets(properties(sheetId,title),protectedRanges),namedRanges(namedRangeId,name,range)",
+        ).await.map_err(SpreadsheetError::Api)?;
+        let structure = SpreadsheetStructure::from_spreadsheet(&spreadsheet);
+        validate_raw_requests(&req.requests, &structure).map_err(SpreadsheetError::InvalidRequest)?;
+        if !req.override_protection {
+            check_protection(&spreadsheet, &raw_request_cells(&req.requests))?;
+        }
+        if req.dry_run {
+            return Ok(SheetsBatchUpdateResponse { dry_run: Some(dry_run_report(&req.requests)), ..Default::default() });
+        }
+        let batch_req = google_sheets4::api::BatchUpdateSpreadsheetRequest {
+            requests: Some(req.requests),
+            include_spreadsheet_in_response: Some(req.include_spreadsheet_in_response),
+            ..Default::default()
+        };
+        let resp = self.client.batch_update_with(&req.spreadsheet_id, batch_req).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(SheetsBatchUpdateResponse {
+            replies: resp.replies.unwrap_or_default(),
+            spreadsheet: resp.updated_spreadsheet,
+            dry_run: None,
+        })
+    }
+}
+
+/// Request kinds whose ranges are only read or referenced, never written.
+const NON_WRITING_REQUESTS: &[&str] = &[
+    "addNamedRange", "updateNamedRange", "deleteNamedRange",
+    "addProtectedRange", "updateProtectedRange", "deleteProtectedRange",
+    "addChart", "updateChartSpec",
+    "setBasicFilter", "clearBasicFilter", "addFilterView", "updateFilterView", "deleteFilterView", "duplicateFilterView",
+];
+
+/// The cells each raw request writes, labelled for `check_protection`. A grid coordinate
+/// such as `updateCells.start` counts as everything below and to the right of it, since
+/// the size of the payload written there is not worked out.
+pub fn raw_request_cells(requests: &[google_sheets4::api::Request]) -> Vec<(String, google_sheets4::api::GridRange)> {
+    use google_sheets4::api::GridRange;
+    use serde_json::Value;
+    fn as_grid(value: &Value) -> Option<GridRange> {
+        let object = value.as_object()?;
+        let index = |key: &str| object.get(key).and_then(Value::as_i64).map(|n| n as i32);
+        let sheet_id = index("sheetId");
+        match object.get("dimension").and_then(Value::as_str) {
+            Some("COLUMNS") => Some(GridRange { sheet_id, start_column_index: index("startIndex"), end_column_index: index("endIndex"), ..Default::default() }),
+            Some(_) => Some(GridRange { sheet_id, start_row_index: index("startIndex"), end_row_index: index("endIndex"), ..Default::default() }),
+            None if object.contains_key("rowIndex") || object.contains_key("columnIndex") => Some(GridRange {
+                sheet_id,
+                start_row_index: Some(index("rowIndex").unwrap_or(0)),
+                start_column_index: Some(index("columnIndex").unwrap_or(0)),
+                ..Default::default()
+            }),
+            None => serde_json::from_value(value.clone()).ok(),
+        }
+    }
+    // `source` is only written by cutPaste; copyPaste and moveDimension just read it.
+    fn walk(value: &Value, writes_source: bool, out: &mut Vec<GridRange>) {
+        match value {
+            Value::Object(map) => {
+                for (key, v) in map {
+                    match key.as_str() {
+                        "range" | "destination" | "start" | "coordinate" => out.extend(as_grid(v)),
+                        "source" if writes_source => out.extend(as_grid(v)),
+                        "ranges" => out.extend(v.as_array().into_iter().flatten().filter_map(as_grid)),
+                        _ => walk(v, writes_source, out),
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| walk(v, writes_source, out)),
+            _ => {}
+        }
+    }
+    let mut cells = Vec::new();
+    for (i, request) in requests.iter().enumerate() {
+        let value = strip_nulls(serde_json::to_value(request).unwrap_or_default());
+        let Some((kind, body)) = value.as_object().and_then(|o| o.iter().next()) else { continue };
+        if NON_WRITING_REQUESTS.contains(&kind.as_str()) {
+            continue;
+        }
+        let mut grids = Vec::new();
+        walk(body, kind == "cutPaste", &mut grids);
+        cells.extend(grids.into_iter().map(|g| (format!("request {} ({})", i, kind), g)));
+    }
+    cells
+}
+
+// ===== Table Read Types =====
+
+#[derive(serde::Deserialize)]
+pub struct ReadTableRequest {
+    pub spreadsheet_id: String,
+    pub range: String, // A1 notation or a named range
+    pub header_row: Option<usize>, // zero-based within the range; detected when omitted
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ReadTableResponse {
+    pub header_row: usize,
+    pub headers: Vec<String>,
+    pub column_types: Vec<crate::table::ColumnType>,
+    pub records: Vec<serde_json::Map<String, serde_json::Value>>,
+    pub total_rows: usize, // data rows before offset/limit
+}
+
+pub struct ReadTableHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ReadTableHandler {
+    type Request = ReadTableRequest;
+    type Response = ReadTableResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let values = read_values(&self.client, &req.spreadsheet_id, &req.range).await?;
+        let table = crate::table::Table::from_values(&values, req.header_row)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        let records = table.rows.iter()
+            .skip(req.offset.unwrap_or(0))
+            .take(req.limit.unwrap_or(usize::MAX))
+            .map(|row| table.record(row))
+            .collect();
+        Ok(ReadTableResponse {
+            header_row: table.header_row,
+            total_rows: table.rows.len(),
+            headers: table.headers,
+            column_types: table.column_types,
+            records,
+        })
+    }
+}
+
+// ===== Upsert Types =====
+
+#[derive(serde::Deserialize)]
+pub struct UpsertRowsRequest {
+    pub spreadsheet_id: String,
+    pub range: String, // the table, e.g. "Customers" or "Customers!A1:F"
+    pub key_column: String, // header name
+    pub rows: Vec<serde_json::Map<String, serde_json::Value>>, // records keyed by header
+    pub header_row: Option<usize>,
+    #[serde(default)]
+    pub override_protection: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UpsertRowsResponse {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub operation_id: Option<String>, // undo journal entry covering the updates and the append
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct UpsertRowsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UpsertRowsHandler {
+    type Request = UpsertRowsRequest;
+    type Response = UpsertRowsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let range = A1Range::parse(&req.range).map_err(SpreadsheetError::InvalidRequest)?;
+        let (_, table_range) = self.client.resolve_range(&req.spreadsheet_id, &range).await
+            .map_err(SpreadsheetError::Api)?;
+        let values = read_values(&self.client, &req.spreadsheet_id, &req.range).await?;
+        let table = crate::table::Table::from_values(&values, req.header_row)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        let plan = crate::table::plan_upsert(&table, &req.key_column, &req.rows)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+
+        // Absolute position of the first data row / column of the table on its sheet.
+        let first_row = table_range.start_row.unwrap_or(0) + table.header_row as u32 + 1;
+        let first_col = table_range.start_col.unwrap_or(0);
+        let cell_range = |row: u32, col: u32, width: usize, height: usize| A1Range {
+            sheet: table_range.sheet.clone(),
+            start_row: Some(row),
+            end_row: Some(row + height as u32),
+            start_col: Some(col),
+            end_col: Some(col + width as u32),
+        }.to_string();
+
+        let updated = plan.updated;
+        let inserted = plan.appends.len();
+        let updates: Vec<(String, Vec<Vec<String>>)> = plan.updates.into_iter()
+            .map(|(row, col, span)| (cell_range(first_row + row as u32, first_col + col as u32, span.len(), 1), vec![span]))
+            .collect();
+        let append_row = first_row + table.rows.len() as u32;
+        if req.dry_run {
+            let append_block = cell_range(append_row, first_col, table.headers.len(), inserted);
+            let mut writes: Vec<(&str, &Vec<Vec<String>>)> = updates.iter().map(|(r, v)| (r.as_str(), v)).collect();
+            if inserted > 0 {
+                writes.push((append_block.as_str(), &plan.appends));
+            }
+            let mut report = preview_writes(&self.client, &req.spreadsheet_id, &writes).await?;
+            report.summary = format!("Would insert {} and update {} row(s); {} unchanged", inserted, updated, plan.unchanged);
+            return Ok(UpsertRowsResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let append_at = cell_range(append_row, first_col, table.headers.len(), 1);
+        let mut writes: Vec<(&str, &Vec<Vec<String>>)> = updates.iter().map(|(r, v)| (r.as_str(), v)).collect();
+        if !req.override_protection {
+            if inserted > 0 {
+                writes.push((append_at.as_str(), &plan.appends));
+            }
+            ensure_unprotected(&self.client, &req.spreadsheet_id, &writes).await?;
+            writes.truncate(updates.len());
+        }
+        let mut backups = backup_ranges(&self.client, &req.spreadsheet_id, &writes).await?;
+        if !updates.is_empty() {
+            self.client.batch_set_sheet_data(&req.spreadsheet_id, &updates).await
+                .map_err(SpreadsheetError::Api)?;
+        }
+        if inserted > 0 {
+            let backup = append_backup(&self.client, append_at.clone(), &plan.appends);
+            let (_, appended) = self.client.append_sheet_data(&req.spreadsheet_id, &append_at, plan.appends).await
+                .map_err(SpreadsheetError::Api)?;
+            backups.extend(backup.map(|b| crate::journal::RangeBackup { range: appended, ..b }));
+        }
+        let operation_id = if backups.is_empty() { None } else { journal_write(&self.client, "upsert_rows", &req.spreadsheet_id, backups) };
+        Ok(UpsertRowsResponse { inserted, updated, unchanged: plan.unchanged, operation_id, dry_run: None })
+    }
+}
+
+// ===== Query Types =====
+
+#[derive(serde::Deserialize)]
+pub struct QuerySheetRequest {
+    pub spreadsheet_id: String,
+    pub range: String, // A1 notation or a named range
+    pub query: String, // e.g. "SELECT name, amount WHERE status = 'open' ORDER BY amount DESC LIMIT 20"
+    pub header_row: Option<usize>,
+}
+
+#[derive(serde::Serialize)]
+pub struct QuerySheetResponse {
+    pub columns: Vec<String>,
+    pub records: Vec<serde_json::Map<String, serde_json::Value>>,
+    pub matched: usize, // rows matching WHERE, before LIMIT/OFFSET
+}
+
+pub struct QuerySheetHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for QuerySheetHandler {
+    type Request = QuerySheetRequest;
+    type Response = QuerySheetResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        // Parse first so a malformed query fails without touching the API.
+        let query = crate::query::parse(&req.query).map_err(SpreadsheetError::InvalidRequest)?;
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let values = read_values(&self.client, &req.spreadsheet_id, &req.range).await?;
+        let table = crate::table::Table::from_values(&values, req.header_row)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        let result = query.execute(&table).map_err(SpreadsheetError::InvalidRequest)?;
+        let types: Vec<crate::table::ColumnType> = result.columns.iter()
+            .map(|c| table.column_index(c).map(|i| table.column_types[i]).unwrap_or(crate::table::ColumnType::Text))
+            .collect();
+        let records = result.rows.iter().map(|row| {
+            result.columns.iter().zip(row).zip(&types)
+                .map(|((c, cell), ty)| (c.clone(), crate::table::typed_value(cell, *ty)))
+                .collect()
+        }).collect();
+        Ok(QuerySheetResponse { columns: result.columns, records, matched: result.matched })
+    }
+}
+
+// ===== Find/Replace Types =====
+
+#[derive(serde::Deserialize)]
+pub struct FindReplaceRequest {
+    pub spreadsheet_id: String,
+    pub find: String,
+    pub replacement: String,
+    #[serde(default)]
+    pub regex: bool, // `find` is a regular expression; `$1` in `replacement` refers to groups
+    #[serde(default)]
+    pub match_case: bool,
+    #[serde(default)]
+    pub entire_cell: bool,
+    #[serde(default)]
+    pub include_formulas: bool, // also search formula text; otherwise formula cells are skipped
+    pub sheet: Option<String>, // limit to one sheet
+    pub range: Option<A1Range>, // limit to a range (or named range); with no sheet either, all sheets
+    #[serde(default, alias = "dry_run")]
+    pub preview: bool, // list matches without changing anything
+}
+
+#[derive(serde::Serialize)]
+pub struct FindMatch {
+    pub cell: String, // A1 notation, e.g. Sheet1!B3
+    pub value: String,
+    pub replaced: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct FindReplaceResponse {
+    pub occurrences: usize,
+    pub values_changed: usize,
+    pub formulas_changed: usize,
+    pub rows_changed: usize,
+    pub sheets_changed: usize,
+    pub matches: Vec<FindMatch>, // preview only, capped at FIND_PREVIEW_LIMIT
+}
+
+pub const FIND_PREVIEW_LIMIT: usize = 500;
+
+/// Finds matching cells in `values`, which was read with the `FORMULA` render option from
+/// the range `origin` (as returned by the API, e.g. `Sheet1!A1:D20`).
+pub fn find_matches(
+    matcher: &crate::search::CellMatcher,
+    replacement: &str,
+    origin: &str,
+    values: &[Vec<String>],
+    include_formulas: bool,
+) -> Result<Vec<FindMatch>, String> {
+    let origin = A1Range::parse(origin)?;
+    let matches = crate::search::matching_cells(matcher, values, include_formulas).into_iter().map(|(r, c)| {
+        let cell = &values[r][c];
+        FindMatch {
+            cell: origin.cell_at(r as u32, c as u32).to_string(),
+            value: cell.clone(),
+            replaced: matcher.replace(cell, replacement),
+        }
+    }).collect();
+    Ok(matches)
+}
+
+/// The A1 ranges to read when scanning: the given range (named ranges expanded), the given
+/// sheet, or every sheet in the spreadsheet.
+async fn scan_ranges(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    range: Option<&A1Range>,
+    sheet: Option<&str>,
+) -> Result<Vec<String>, SpreadsheetError> {
+    let whole_sheet = |title: String| A1Range { sheet: Some(title), ..Default::default() }.to_string();
+    Ok(match (range, sheet) {
+        (Some(range), _) => vec![client.resolve_range(spreadsheet_id, range).await
+            .map_err(SpreadsheetError::Api)?.1.to_string()],
+        (None, Some(sheet)) => vec![whole_sheet(sheet.to_string())],
+        (None, None) => client.sheet_titles(spreadsheet_id).await
+            .map_err(SpreadsheetError::Api)?
+            .into_iter()
+            .map(whole_sheet)
+            .collect(),
+    })
+}
+
+pub struct FindReplaceHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+impl FindReplaceHandler {
+    async fn preview(&self, req: &FindReplaceRequest, matcher: &crate::search::CellMatcher) -> Result<FindReplaceResponse, SpreadsheetError> {
+        let ranges = scan_ranges(&self.client, &req.spreadsheet_id, req.range.as_ref(), req.sheet.as_deref()).await?;
+        let read = self.client.get_values_rendered(&req.spreadsheet_id, &ranges, "FORMULA").await
+            .map_err(SpreadsheetError::Api)?;
+        let mut matches = Vec::new();
+        for (origin, values) in &read {
+            matches.extend(find_matches(matcher, &req.replacement, origin, values, req.include_formulas)
+                .map_err(SpreadsheetError::Api)?);
+        }
+        let occurrences = matches.iter().map(|m| matcher.count(&m.value)).sum();
+        let formulas_changed = matches.iter().filter(|m| crate::search::is_formula(&m.value)).count();
+        let rows: std::collections::HashSet<(Option<String>, Option<u32>)> = matches.iter()
+            .filter_map(|m| A1Range::parse(&m.cell).ok())
+            .map(|a| (a.sheet, a.start_row))
+            .collect();
+        let sheets: std::collections::HashSet<&Option<String>> = rows.iter().map(|(s, _)| s).collect();
+        let sheets_changed = sheets.len();
+        let rows_changed = rows.len();
+        let values_changed = matches.len() - formulas_changed;
+        matches.truncate(FIND_PREVIEW_LIMIT);
+        Ok(FindReplaceResponse { occurrences, values_changed, formulas_changed, rows_changed, sheets_changed, matches })
+    }
+}
+
+#[async_trait]
+impl McpHandler for FindReplaceHandler {
+    type Request = FindReplaceRequest;
+    type Response = FindReplaceResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{FindReplaceRequest as FindReplace, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.range.is_some() && req.sheet.is_some() {
+            return Err(SpreadsheetError::InvalidRequest("Specify either sheet or range, not both".to_string()));
+        }
+        // Validates the pattern up front; the API reports a bad regex only as a generic 400.
+        let matcher = crate::search::CellMatcher::new(&req.find, req.regex, req.match_case, req.entire_cell)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        if req.preview {
+            return self.preview(&req, &matcher).await;
+        }
+        let mut find_replace = FindReplace {
+            find: Some(req.find),
+            replacement: Some(req.replacement),
+            match_case: Some(req.match_case),
+            match_entire_cell: Some(req.entire_cell),
+            search_by_regex: Some(req.regex),
+            include_formulas: Some(req.include_formulas),
+            ..Default::default()
+        };
+        match (&req.range, &req.sheet) {
+            (Some(range), _) => {
+                find_replace.range = Some(self.client.grid_range(&req.spreadsheet_id, range).await
+                    .map_err(SpreadsheetError::Api)?);
+            }
+            (None, Some(sheet)) => {
+                find_replace.sheet_id = Some(self.client.sheet_id(&req.spreadsheet_id, Some(sheet)).await
+                    .map_err(SpreadsheetError::Api)?);
+            }
+            (None, None) => find_replace.all_sheets = Some(true),
+        }
+        let request = Request { find_replace: Some(find_replace), ..Default::default() };
+        let resp = self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        let reply = resp.replies.unwrap_or_default().into_iter().next()
+            .and_then(|r| r.find_replace)
+            .unwrap_or_default();
+        Ok(FindReplaceResponse {
+            occurrences: reply.occurrences_changed.unwrap_or(0) as usize,
+            values_changed: reply.values_changed.unwrap_or(0) as usize,
+            formulas_changed: reply.formulas_changed.unwrap_or(0) as usize,
+            rows_changed: reply.rows_changed.unwrap_or(0) as usize,
+            sheets_changed: reply.sheets_changed.unwrap_or(0) as usize,
+            matches: Vec::new(),
+        })
+    }
+}
+
+// ===== Search Types =====
+
+#[derive(serde::Deserialize)]
+pub struct SearchCellsRequest {
+    pub spreadsheet_id: String,
+    pub query: String,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub match_case: bool,
+    #[serde(default)]
+    pub entire_cell: bool,
+    pub range: Option<A1Range>, // A1 notation, sheet or named range; all sheets when omitted
+    pub offset: Option<usize>,
+    pub limit: Option<usize>, // default SEARCH_PAGE_SIZE
+}
+
+#[derive(serde::Serialize)]
+pub struct CellMatch {
+    pub cell: String, // A1 notation, e.g. 'Q1 Sales'!C14
+    pub value: String,
+    pub row: Vec<String>, // the whole row as read, for context
+}
+
+#[derive(serde::Serialize)]
+pub struct SearchCellsResponse {
+    pub matches: Vec<CellMatch>,
+    pub total: usize,
+    pub next_offset: Option<usize>, // pass as `offset` to fetch the next page
+}
+
+pub const SEARCH_PAGE_SIZE: usize = 50;
+
+pub struct SearchCellsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SearchCellsHandler {
+    type Request = SearchCellsRequest;
+    type Response = SearchCellsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let matcher = crate::search::CellMatcher::new(&req.query, req.regex, req.match_case, req.entire_cell)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        let offset = req.offset.unwrap_or(0);
+        let limit = req.limit.unwrap_or(SEARCH_PAGE_SIZE);
+        let ranges = scan_ranges(&self.client, &req.spreadsheet_id, req.range.as_ref(), None).await?;
+        // Search what users see, so formatted numbers and dates match as displayed.
+        let read = self.client.get_values_rendered(&req.spreadsheet_id, &ranges, "FORMATTED_VALUE").await
+            .map_err(SpreadsheetError::Api)?;
+        let mut total = 0;
+        let mut matches = Vec::new();
+        for (origin, values) in &read {
+            let origin = A1Range::parse(origin).map_err(SpreadsheetError::Api)?;
+            for (r, c) in crate::search::matching_cells(&matcher, values, true) {
+                if total >= offset && matches.len() < limit {
+                    matches.push(CellMatch {
+                        cell: origin.cell_at(r as u32, c as u32).to_string(),
+                        value: values[r][c].clone(),
+                        row: values[r].clone(),
+                    });
+                }
+                total += 1;
+            }
+        }
+        let next_offset = Some(offset + matches.len()).filter(|&next| next < total);
+        Ok(SearchCellsResponse { matches, total, next_offset })
+    }
+}
+
+// ===== Sort and Filter Types =====
+
+#[derive(serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub column: String, // header name, or column letter such as "C"
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ColumnFilter {
+    pub column: String, // header name, or column letter such as "C"
+    #[serde(default)]
+    pub hidden_values: Vec<String>,
+    pub condition: Option<String>, // e.g. TEXT_CONTAINS, NUMBER_GREATER, BLANK
+    #[serde(default)]
+    pub values: Vec<String>, // condition operands
+}
+
+/// Resolves a column given by header name (exact, then case-insensitive) or by letter
+/// to an absolute sheet column index. Header names win over letters, so a column headed
+/// "ID" is found by name.
+pub fn resolve_column(column: &str, headers: &[String], start_col: u32) -> Result<i32, String> {
+    let by_name = headers.iter().position(|h| h == column)
+        .or_else(|| headers.iter().position(|h| h.eq_ignore_ascii_case(column)));
+    if let Some(i) = by_name {
+        return Ok((start_col + i as u32) as i32);
+    }
+    crate::a1::letters_to_column(column)
+        .map(|c| c as i32)
+        .map_err(|_| format!("Unknown column: {}", column))
+}
+
+fn sort_specs(keys: &[SortKey], headers: &[String], start_col: u32) -> Result<Vec<google_sheets4::api::SortSpec>, String> {
+    keys.iter().map(|key| Ok(google_sheets4::api::SortSpec {
+        dimension_index: Some(resolve_column(&key.column, headers, start_col)?),
+        sort_order: Some(if key.descending { "DESCENDING" } else { "ASCENDING" }.to_string()),
+        ..Default::default()
+    })).collect()
+}
+
+fn filter_specs(filters: &[ColumnFilter], headers: &[String], start_col: u32) -> Result<Vec<google_sheets4::api::FilterSpec>, String> {
+    use google_sheets4::api::{BooleanCondition, ConditionValue, FilterCriteria, FilterSpec};
+    filters.iter().map(|f| {
+        let condition = f.condition.as_ref().map(|c| BooleanCondition {
+            type_: Some(c.to_uppercase()),
+            values: if f.values.is_empty() {
+                None
+            } else {
+                Some(f.values.iter().map(|v| ConditionValue { user_entered_value: Some(v.clone()), ..Default::default() }).collect())
+            },
+        });
+        Ok(FilterSpec {
+            column_index: Some(resolve_column(&f.column, headers, start_col)?),
+            filter_criteria: Some(FilterCriteria {
+                hidden_values: if f.hidden_values.is_empty() { None } else { Some(f.hidden_values.clone()) },
+                condition,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }).collect()
+}
+
+/// Reads the formatted values of one range, as a user sees them in the sheet.
+async fn read_values(client: &GoogleApiClient, spreadsheet_id: &str, range: &str) -> Result<Vec<Vec<String>>, SpreadsheetError> {
+    let read = client.get_values_rendered(spreadsheet_id, &[range.to_string()], "FORMATTED_VALUE").await
+        .map_err(SpreadsheetError::Api)?;
+    Ok(read.into_iter().next().map(|(_, values)| values).unwrap_or_default())
+}
+
+/// Reads the first row of a resolved range, used to look up columns by header name.
+async fn range_headers(client: &GoogleApiClient, spreadsheet_id: &str, range: &A1Range) -> Result<Vec<String>, SpreadsheetError> {
+    let start_row = range.start_row.unwrap_or(0);
+    let first_row = A1Range { start_row: Some(start_row), end_row: Some(start_row + 1), ..range.clone() };
+    let read = client.get_values_rendered(spreadsheet_id, &[first_row.to_string()], "FORMATTED_VALUE").await
+        .map_err(SpreadsheetError::Api)?;
+    Ok(read.into_iter().next().and_then(|(_, values)| values.into_iter().next()).unwrap_or_default())
+}
+
+#[derive(serde::Deserialize)]
+pub struct SortRangeRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range,
+    pub keys: Vec<SortKey>, // applied in order; later keys break ties
+    #[serde(default)]
+    pub has_header: bool, // keep the first row in place and allow keys by header name
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SortRangeResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SetBasicFilterRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range, // first row is the header row
+    #[serde(default)]
+    pub sort: Vec<SortKey>,
+    #[serde(default)]
+    pub filters: Vec<ColumnFilter>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SetBasicFilterResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ClearBasicFilterRequest {
+    pub spreadsheet_id: String,
+    pub sheet: Option<String>, // defaults to the first sheet
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct ClearBasicFilterResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateFilterViewRequest {
+    pub spreadsheet_id: String,
+    pub title: String,
+    pub range: A1Range, // first row is the header row
+    #[serde(default)]
+    pub sort: Vec<SortKey>,
+    #[serde(default)]
+    pub filters: Vec<ColumnFilter>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct CreateFilterViewResponse {
+    pub filter_view_id: i32,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteFilterViewRequest {
+    pub spreadsheet_id: String,
+    pub filter_view_id: i32,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct DeleteFilterViewResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct SortRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SortRangeHandler {
+    type Request = SortRangeRequest;
+    type Response = SortRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Request, SortRangeRequest as SortRange};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.keys.is_empty() {
+            return Err(SpreadsheetError::InvalidRequest("At least one sort key is required".to_string()));
+        }
+        let (mut grid, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let headers = if req.has_header {
+            grid.start_row_index = Some(grid.start_row_index.unwrap_or(0) + 1);
+            range_headers(&self.client, &req.spreadsheet_id, &concrete).await?
+        } else {
+            Vec::new()
+        };
+        let specs = sort_specs(&req.keys, &headers, concrete.start_col.unwrap_or(0))
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        let request = Request {
+            sort_range: Some(SortRange { range: Some(grid), sort_specs: Some(specs) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(SortRangeResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(SortRangeResponse { success: true, dry_run: None })
+    }
+}
+
+pub struct SetBasicFilterHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SetBasicFilterHandler {
+    type Request = SetBasicFilterRequest;
+    type Response = SetBasicFilterResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{BasicFilter, Request, SetBasicFilterRequest as SetBasicFilter};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let (grid, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let headers = range_headers(&self.client, &req.spreadsheet_id, &concrete).await?;
+        let start_col = concrete.start_col.unwrap_or(0);
+        let filter = BasicFilter {
+            range: Some(grid),
+            sort_specs: Some(sort_specs(&req.sort, &headers, start_col).map_err(SpreadsheetError::InvalidRequest)?),
+            filter_specs: Some(filter_specs(&req.filters, &headers, start_col).map_err(SpreadsheetError::InvalidRequest)?),
+            ..Default::default()
+        };
+        let request = Request {
+            set_basic_filter: Some(SetBasicFilter { filter: Some(filter) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(SetBasicFilterResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(SetBasicFilterResponse { success: true, dry_run: None })
+    }
+}
+
+pub struct ClearBasicFilterHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ClearBasicFilterHandler {
+    type Request = ClearBasicFilterRequest;
+    type Response = ClearBasicFilterResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{ClearBasicFilterRequest as ClearBasicFilter, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let sheet_id = self.client.sheet_id(&req.spreadsheet_id, req.sheet.as_deref()).await
+            .map_err(SpreadsheetError::Api)?;
+        let request = Request {
+            clear_basic_filter: Some(ClearBasicFilter { sheet_id: Some(sheet_id) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(ClearBasicFilterResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(ClearBasicFilterResponse { success: true, dry_run: None })
+    }
+}
+
+pub struct CreateFilterViewHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for CreateFilterViewHandler {
+    type Request = CreateFilterViewRequest;
+    type Response = CreateFilterViewResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{AddFilterViewRequest, FilterView, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let (grid, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let headers = range_headers(&self.client, &req.spreadsheet_id, &concrete).await?;
+        let start_col = concrete.start_col.unwrap_or(0);
+        let filter = FilterView {
+            title: Some(req.title),
+            range: Some(grid),
+            sort_specs: Some(sort_specs(&req.sort, &headers, start_col).map_err(SpreadsheetError::InvalidRequest)?),
+            filter_specs: Some(filter_specs(&req.filters, &headers, start_col).map_err(SpreadsheetError::InvalidRequest)?),
+            ..Default::default()
+        };
+        let request = Request {
+            add_filter_view: Some(AddFilterViewRequest { filter: Some(filter) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(CreateFilterViewResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        let resp = self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        let filter_view_id = resp.replies.unwrap_or_default().into_iter().next()
+            .and_then(|r| r.add_filter_view)
+            .and_then(|a| a.filter)
+            .and_then(|f| f.filter_view_id)
+            .unwrap_or_default();
+        Ok(CreateFilterViewResponse { filter_view_id, dry_run: None })
+    }
+}
+
+pub struct DeleteFilterViewHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for DeleteFilterViewHandler {
+    type Request = DeleteFilterViewRequest;
+    type Response = DeleteFilterViewResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{DeleteFilterViewRequest as DeleteFilterView, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let request = Request {
+            delete_filter_view: Some(DeleteFilterView { filter_id: Some(req.filter_view_id) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(DeleteFilterViewResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(DeleteFilterViewResponse { success: true, dry_run: None })
+    }
+}
+
+// ===== Cell Detail Types =====
+
+#[derive(serde::Deserialize)]
+pub struct GetCellsRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range, // A1 notation or a named range
+}
+
+#[derive(serde::Serialize, Debug, PartialEq)]
+pub struct CellInfo {
+    pub cell: String, // A1 notation
+    pub formula: Option<String>, // e.g. =SUM(B2:B10); None for literal values
+    pub value: serde_json::Value, // effective (computed) value; errors as {"error": "#DIV/0!", ...}
+    pub formatted: Option<String>, // as displayed
+    pub note: Option<String>,
+    pub hyperlink: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct GetCellsResponse {
+    pub cells: Vec<CellInfo>, // non-empty cells in row-major order
+}
+
+const CELL_DETAIL_FIELDS: &str =
+    "sheets(properties(title),data(startRow,startColumn,rowData(values(userEnteredValue,effectiveValue,formattedValue,note,hyperlink))))";
+
+pub fn extended_value_json(value: &google_sheets4::api::ExtendedValue) -> serde_json::Value {
+    use serde_json::Value;
+    if let Some(n) = value.number_value {
+        return serde_json::Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null);
+    }
+    if let Some(b) = value.bool_value {
+        return Value::Bool(b);
+    }
+    if let Some(s) = &value.string_value {
+        return Value::String(s.clone());
+    }
+    if let Some(e) = &value.error_value {
+        return serde_json::json!({ "error": e.type_, "message": e.message });
+    }
+    Value::Null
+}
+
+/// Summarizes one cell from grid data; `None` for cells with no content, note or link.
+pub fn cell_info(cell: String, data: &google_sheets4::api::CellData) -> Option<CellInfo> {
+    let formula = data.user_entered_value.as_ref().and_then(|v| v.formula_value.clone());
+    let value = data.effective_value.as_ref().map(extended_value_json).unwrap_or(serde_json::Value::Null);
+    if formula.is_none() && value.is_null() && data.formatted_value.is_none() && data.note.is_none() && data.hyperlink.is_none() {
+        return None;
+    }
+    Some(CellInfo {
+        cell,
+        formula,
+        value,
+        formatted: data.formatted_value.clone(),
+        note: data.note.clone(),
+        hyperlink: data.hyperlink.clone(),
+    })
+}
+
+pub struct GetCellsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for GetCellsHandler {
+    type Request = GetCellsRequest;
+    type Response = GetCellsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let (_, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let spreadsheet = self.client.get_grid_data(&req.spreadsheet_id, &concrete.to_string(), CELL_DETAIL_FIELDS).await
+            .map_err(SpreadsheetError::Api)?;
+        let mut cells = Vec::new();
+        for sheet in spreadsheet.sheets.unwrap_or_default() {
+            let title = sheet.properties.and_then(|p| p.title);
+            for grid in sheet.data.unwrap_or_default() {
+                let origin = A1Range {
+                    sheet: title.clone(),
+                    start_row: Some(grid.start_row.unwrap_or(0) as u32),
+                    start_col: Some(grid.start_column.unwrap_or(0) as u32),
+                    ..Default::default()
+                };
+                for (r, row) in grid.row_data.unwrap_or_default().iter().enumerate() {
+                    for (c, data) in row.values.as_deref().unwrap_or_default().iter().enumerate() {
+                        cells.extend(cell_info(origin.cell_at(r as u32, c as u32).to_string(), data));
+                    }
+                }
+            }
+        }
+        Ok(GetCellsResponse { cells })
+    }
+}
+
+// ===== Note and Hyperlink Types =====
+
+#[derive(serde::Deserialize)]
+pub struct SetNoteRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range, // every cell in the range gets the note; must be bounded
+    pub note: String,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SetNoteResponse {
+    pub updated_cells: usize,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ClearNotesRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct ClearNotesResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SetHyperlinkRequest {
+    pub spreadsheet_id: String,
+    pub cell: A1Range, // a single cell, e.g. Findings!D4
+    pub url: String,
+    pub text: Option<String>, // display text; defaults to the URL
+    #[serde(default)]
+    pub as_formula: bool, // write =HYPERLINK(url, text) instead of a rich-text link
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SetHyperlinkResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+fn validate_link_url(url: &str) -> Result<(), String> {
+    let lower = url.to_ascii_lowercase();
+    if ["http://", "https://", "mailto:"].iter().any(|p| lower.starts_with(p)) && !url.contains(char::is_whitespace) {
+        Ok(())
+    } else {
+        Err(format!("Unsupported link URL: {}", url))
+    }
+}
+
+/// `=HYPERLINK("url", "text")` with embedded quotes escaped.
+pub fn hyperlink_formula(url: &str, text: &str) -> String {
+    format!("=HYPERLINK(\"{}\", \"{}\")", url.replace('"', "\"\""), text.replace('"', "\"\""))
+}
+
+/// Cell data for a link, written with the mask `userEnteredValue,textFormatRuns` so that
+/// a formula link also clears any rich-text link left from before.
+pub fn hyperlink_cell(url: &str, text: &str, as_formula: bool) -> google_sheets4::api::CellData {
+    use google_sheets4::api::{CellData, ExtendedValue, Link, TextFormat, TextFormatRun};
+    if as_formula {
+        return CellData {
+            user_entered_value: Some(ExtendedValue { formula_value: Some(hyperlink_formula(url, text)), ..Default::default() }),
+            ..Default::default()
+        };
+    }
+    CellData {
+        user_entered_value: Some(ExtendedValue { string_value: Some(text.to_string()), ..Default::default() }),
+        text_format_runs: Some(vec![TextFormatRun {
+            start_index: Some(0),
+            format: Some(TextFormat { link: Some(Link { uri: Some(url.to_string()) }), ..Default::default() }),
+        }]),
+        ..Default::default()
+    }
+}
+
+pub struct SetNoteHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SetNoteHandler {
+    type Request = SetNoteRequest;
+    type Response = SetNoteResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{CellData, Request, RowData, UpdateCellsRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let (grid, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let (rows, cols) = concrete.dimensions()
+            .ok_or_else(|| SpreadsheetError::InvalidRequest(format!("Range must be bounded: {}", concrete)))?;
+        let row = RowData {
+            values: Some(vec![CellData { note: Some(req.note), ..Default::default() }; cols as usize]),
+        };
+        let request = Request {
+            update_cells: Some(UpdateCellsRequest {
+                range: Some(grid),
+                rows: Some(vec![row; rows as usize]),
+                fields: Some(google_sheets4::FieldMask::new(&["note"])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(SetNoteResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(SetNoteResponse { updated_cells: (rows * cols) as usize, dry_run: None })
+    }
+}
+
+pub struct ClearNotesHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ClearNotesHandler {
+    type Request = ClearNotesRequest;
+    type Response = ClearNotesResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Request, UpdateCellsRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let grid = self.client.grid_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        // With a range and no rows, updateCells clears the masked fields across the range.
+        let request = Request {
+            update_cells: Some(UpdateCellsRequest {
+                range: Some(grid),
+                fields: Some(google_sheets4::FieldMask::new(&["note"])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(ClearNotesResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(ClearNotesResponse { success: true, dry_run: None })
+    }
+}
+
+pub struct SetHyperlinkHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SetHyperlinkHandler {
+    type Request = SetHyperlinkRequest;
+    type Response = SetHyperlinkResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{GridCoordinate, Request, RowData, UpdateCellsRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        validate_link_url(&req.url).map_err(SpreadsheetError::InvalidRequest)?;
+        let (grid, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.cell).await
+            .map_err(SpreadsheetError::Api)?;
+        if concrete.dimensions() != Some((1, 1)) {
+            return Err(SpreadsheetError::InvalidRequest(format!("Expected a single cell: {}", concrete)));
+        }
+        let text = req.text.as_deref().unwrap_or(&req.url);
+        let cell = hyperlink_cell(&req.url, text, req.as_formula);
+        let request = Request {
+            update_cells: Some(UpdateCellsRequest {
+                start: Some(GridCoordinate {
+                    sheet_id: grid.sheet_id,
+                    row_index: grid.start_row_index,
+                    column_index: grid.start_column_index,
+                }),
+                rows: Some(vec![RowData { values: Some(vec![cell]) }]),
+                fields: Some(google_sheets4::FieldMask::new(&["userEnteredValue", "textFormatRuns"])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(SetHyperlinkResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(SetHyperlinkResponse { success: true, dry_run: None })
+    }
+}
+
+// ===== Merge and Freeze Types =====
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeType {
+    #[default]
+    All, // one cell covering the whole range
+    Columns, // one merged cell per column
+    Rows, // one merged cell per row
+}
+
+impl MergeType {
+    fn api_name(self) -> &'static str {
+        match self {
+            MergeType::All => "MERGE_ALL",
+            MergeType::Columns => "MERGE_COLUMNS",
+            MergeType::Rows => "MERGE_ROWS",
+        }
+    }
+}
+
+/// Rejects merges the API would treat as a no-op or an error: unbounded ranges and
+/// ranges too narrow to merge along the requested axis.
+pub fn validate_merge(range: &A1Range, merge_type: MergeType) -> Result<(), String> {
+    let (rows, cols) = range.dimensions().ok_or_else(|| format!("Merge range must be bounded: {}", range))?;
+    let mergeable = match merge_type {
+        MergeType::All => rows * cols > 1,
+        MergeType::Columns => rows > 1,
+        MergeType::Rows => cols > 1,
+    };
+    if mergeable {
+        Ok(())
+    } else {
+        Err(format!("Nothing to merge in {} with {}", range, merge_type.api_name()))
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct MergeCellsRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range,
+    #[serde(default)]
+    pub merge_type: MergeType,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct MergeCellsResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct UnmergeCellsRequest {
+    pub spreadsheet_id: String,
+    pub range: A1Range, // every merge inside the range is undone
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UnmergeCellsResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct SetFrozenRequest {
+    pub spreadsheet_id: String,
+    pub sheet: Option<String>, // defaults to the first sheet
+    pub rows: Option<i32>, // 0 unfreezes; omitted leaves rows unchanged
+    pub columns: Option<i32>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct SetFrozenResponse {
+    pub success: bool,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct MergeCellsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for MergeCellsHandler {
+    type Request = MergeCellsRequest;
+    type Response = MergeCellsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{MergeCellsRequest as MergeCells, Request};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let (grid, concrete) = self.client.resolve_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        validate_merge(&concrete, req.merge_type).map_err(SpreadsheetError::InvalidRequest)?;
+        let request = Request {
+            merge_cells: Some(MergeCells {
+                range: Some(grid),
+                merge_type: Some(req.merge_type.api_name().to_string()),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(MergeCellsResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(MergeCellsResponse { success: true, dry_run: None })
+    }
+}
+
+pub struct UnmergeCellsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UnmergeCellsHandler {
+    type Request = UnmergeCellsRequest;
+    type Response = UnmergeCellsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{Request, UnmergeCellsRequest as UnmergeCells};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let grid = self.client.grid_range(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let request = Request {
+            unmerge_cells: Some(UnmergeCells { range: Some(grid) }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(UnmergeCellsResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(UnmergeCellsResponse { success: true, dry_run: None })
+    }
+}
+
+pub struct SetFrozenHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SetFrozenHandler {
+    type Request = SetFrozenRequest;
+    type Response = SetFrozenResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use google_sheets4::api::{GridProperties, Request, SheetProperties, UpdateSheetPropertiesRequest};
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let mut fields = Vec::new();
+        if req.rows.is_some() {
+            fields.push("gridProperties.frozenRowCount");
+        }
+        if req.columns.is_some() {
+            fields.push("gridProperties.frozenColumnCount");
+        }
+        if fields.is_empty() {
+            return Err(SpreadsheetError::InvalidRequest("Nothing to update: set rows and/or columns".to_string()));
+        }
+        if req.rows.unwrap_or(0) < 0 || req.columns.unwrap_or(0) < 0 {
+            return Err(SpreadsheetError::InvalidRequest("Frozen counts must not be negative".to_string()));
+        }
+        let sheet_id = self.client.sheet_id(&req.spreadsheet_id, req.sheet.as_deref()).await
+            .map_err(SpreadsheetError::Api)?;
+        let properties = SheetProperties {
+            sheet_id: Some(sheet_id),
+            grid_properties: Some(GridProperties {
+                frozen_row_count: req.rows,
+                frozen_column_count: req.columns,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let request = Request {
+            update_sheet_properties: Some(UpdateSheetPropertiesRequest {
+                properties: Some(properties),
+                fields: Some(google_sheets4::FieldMask::new(&fields)),
+            }),
+            ..Default::default()
+        };
+        if req.dry_run {
+            return Ok(SetFrozenResponse { dry_run: Some(dry_run_report(std::slice::from_ref(&request))), ..Default::default() });
+        }
+        self.client.batch_update(&req.spreadsheet_id, vec![request]).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(SetFrozenResponse { success: true, dry_run: None })
+    }
+}
+
+// ===== CSV Import Types =====
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    #[default]
+    Create, // add a new sheet; fails if it exists
+    Replace, // clear the sheet's values, then write
+    Append, // add rows after the existing table
+}
+
+#[derive(serde::Deserialize)]
+pub struct ImportCsvRequest {
+    pub spreadsheet_id: String,
+    pub sheet: String,
+    pub csv: String, // CSV/TSV text, RFC 4180 quoting
+    pub delimiter: Option<String>, // e.g. "," or "\t"; detected from the first line when omitted
+    #[serde(default = "default_true")]
+    pub has_header: bool, // in append mode the header row is dropped
+    #[serde(default)]
+    pub mode: ImportMode,
+    pub start_cell: Option<String>, // top-left cell for create/replace, default A1
+    pub chunk_rows: Option<usize>, // rows per batch_set_sheet_data call, default IMPORT_CHUNK_ROWS
+    #[serde(default)]
+    pub override_protection: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct ImportCsvResponse {
+    pub rows_written: usize,
+    pub columns: usize,
+    pub chunks: usize,
+    pub operation_ids: Vec<String>, // undo journal entries, oldest first; undo them newest first
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub const IMPORT_CHUNK_ROWS: usize = 1000;
+
+/// Splits `rows` into writes of at most `chunk_rows` rows, placed one below the other
+/// starting at `origin` on `sheet`.
+pub fn chunk_writes(sheet: &str, origin: (u32, u32), rows: Vec<Vec<String>>, chunk_rows: usize) -> Vec<(String, Vec<Vec<String>>)> {
+    let (row0, col0) = origin;
+    let mut writes = Vec::new();
+    let mut rows = rows.into_iter().peekable();
+    let mut offset = 0;
+    while rows.peek().is_some() {
+        let chunk: Vec<Vec<String>> = rows.by_ref().take(chunk_rows.max(1)).collect();
+        let start = A1Range { sheet: Some(sheet.to_string()), ..Default::default() }.cell_at(row0 + offset, col0);
+        offset += chunk.len() as u32;
+        writes.push((start.to_string(), chunk));
+    }
+    writes
+}
+
+pub struct ImportCsvHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+impl ImportCsvHandler {
+    /// Creates the sheet (create mode) or clears it (replace mode), making sure the grid is
+    /// large enough for `rows` x `cols` values starting at `origin`.
+    async fn prepare_sheet(&self, req: &ImportCsvRequest, origin: (u32, u32), rows: usize, cols: usize) -> Result<Option<String>, SpreadsheetError> {
+        use google_sheets4::api::{AddSheetRequest, AppendDimensionRequest, GridProperties, GridRange, Request, SheetProperties, UpdateCellsRequest};
+        let needed_rows = (origin.0 as usize + rows) as i32;
+        let needed_cols = (origin.1 as usize + cols) as i32;
+        let mut requests = Vec::new();
+        let mut backups = Vec::new();
+        if req.mode == ImportMode::Create {
+            requests.push(Request {
+                add_sheet: Some(AddSheetRequest {
+                    properties: Some(SheetProperties {
+                        title: Some(req.sheet.clone()),
+                        grid_properties: Some(GridProperties {
+                            row_count: Some(needed_rows.max(1000)),
+                            column_count: Some(needed_cols.max(26)),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            });
+        } else {
+            let spreadsheet = self.client.get_spreadsheet(&req.spreadsheet_id, "sheets(properties(sheetId,title,gridProperties),protectedRanges)").await
+                .map_err(SpreadsheetError::Api)?;
+            let properties = spreadsheet.sheets.iter().flatten()
+                .filter_map(|s| s.properties.as_ref())
+                .find(|p| p.title.as_deref() == Some(req.sheet.as_str()))
+                .ok_or_else(|| SpreadsheetError::InvalidRequest(format!("Sheet not found: {}", req.sheet)))?;
+            let sheet_id = properties.sheet_id.unwrap_or(0);
+            let grid = properties.grid_properties.clone().unwrap_or_default();
+            // Replacing clears every cell of the sheet, so check all of it before anything is written.
+            if !req.override_protection {
+                let whole_sheet = GridRange { sheet_id: Some(sheet_id), ..Default::default() };
+                check_protection(&spreadsheet, &[(format!("sheet {:?}", req.sheet), whole_sheet)])?;
+            }
+            let sheet = A1Range { sheet: Some(req.sheet.clone()), ..Default::default() }.to_string();
+            backups = backup_ranges(&self.client, &req.spreadsheet_id, &[(sheet.as_str(), &Vec::new())]).await?;
+            requests.push(Request {
+                update_cells: Some(UpdateCellsRequest {
+                    range: Some(GridRange { sheet_id: Some(sheet_id), ..Default::default() }),
+                    fields: Some(google_sheets4::FieldMask::new(&["userEnteredValue"])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+            for (dimension, have, need) in [("ROWS", grid.row_count.unwrap_or(0), needed_rows), ("COLUMNS", grid.column_count.unwrap_or(0), needed_cols)] {
+                if need > have {
+                    requests.push(Request {
+                        append_dimension: Some(AppendDimensionRequest {
+                            sheet_id: Some(sheet_id),
+                            dimension: Some(dimension.to_string()),
+                            length: Some(need - have),
+                        }),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        self.client.batch_update(&req.spreadsheet_id, requests).await
+            .map_err(SpreadsheetError::Api)?;
+        // The chunked writes that follow back up the blanked sheet, so journal the clear itself.
+        Ok(if backups.is_empty() { None } else { journal_write(&self.client, "import_csv", &req.spreadsheet_id, backups) })
+    }
+
+    /// The cells an import would change. Replace mode also reports the existing cells it
+    /// would clear; create mode starts from an empty sheet.
+    async fn preview(&self, req: &ImportCsvRequest, rows: &[Vec<String>]) -> Result<Vec<CellDiff>, SpreadsheetError> {
+        let sheet = A1Range { sheet: Some(req.sheet.clone()), ..Default::default() };
+        let current = match req.mode {
+            ImportMode::Create => Vec::new(),
+            ImportMode::Replace | ImportMode::Append => read_values(&self.client, &req.spreadsheet_id, &sheet.to_string()).await?,
+        };
+        if req.mode == ImportMode::Append {
+            return Ok(cell_diffs(&sheet.cell_at(current.len() as u32, 0), &[], rows));
+        }
+        let start = A1Range::parse(req.start_cell.as_deref().unwrap_or("A1")).map_err(SpreadsheetError::InvalidRequest)?;
+        let (row0, col0) = (start.start_row.unwrap_or(0) as usize, start.start_col.unwrap_or(0) as usize);
+        // Everything currently on the sheet is blanked, then the import is laid over it.
+        let mut after: Vec<Vec<String>> = current.iter().map(|row| vec![String::new(); row.len()]).collect();
+        for (r, row) in rows.iter().enumerate() {
+            if after.len() <= row0 + r {
+                after.resize(row0 + r + 1, Vec::new());
+            }
+            let target = &mut after[row0 + r];
+            if target.len() < col0 + row.len() {
+                target.resize(col0 + row.len(), String::new());
+            }
+            target[col0..col0 + row.len()].clone_from_slice(row);
+        }
+        Ok(cell_diffs(&sheet, &current, &after))
+    }
+}
+
+#[async_trait]
+impl McpHandler for ImportCsvHandler {
+    type Request = ImportCsvRequest;
+    type Response = ImportCsvResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let delimiter = match &req.delimiter {
+            Some(d) => crate::delimited::parse_delimiter(d).map_err(SpreadsheetError::InvalidRequest)?,
+            None => crate::delimited::sniff_delimiter(&req.csv),
+        };
+        let mut rows = crate::delimited::parse_delimited(&req.csv, delimiter, None)
+            .map_err(SpreadsheetError::InvalidRequest)?;
+        if req.mode == ImportMode::Append && req.has_header && !rows.is_empty() {
+            rows.remove(0);
+        }
+        let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let rows_written = rows.len();
+        let chunk_rows = req.chunk_rows.unwrap_or(IMPORT_CHUNK_ROWS);
+        if req.dry_run {
+            let action = match req.mode {
+                ImportMode::Create => "create sheet",
+                ImportMode::Replace => "replace the contents of sheet",
+                ImportMode::Append => "append to sheet",
+            };
+            let changes = self.preview(&req, &rows).await?;
+            let report = DryRunReport {
+                summary: format!("Would {} {:?}: {} rows x {} columns in {} chunk(s); {} cells would change",
+                    action, req.sheet, rows_written, columns, rows_written.div_ceil(chunk_rows.max(1)), changes.len()),
+                affected_cells: Some(rows.iter().map(|r| r.len()).sum()),
+                changes,
+                ..Default::default()
+            };
+            return Ok(ImportCsvResponse { dry_run: Some(report), ..Default::default() });
+        }
+
+        if req.mode == ImportMode::Append {
+            if rows.is_empty() {
+                return Ok(ImportCsvResponse { columns, ..Default::default() });
+            }
+            let sheet = A1Range { sheet: Some(req.sheet.clone()), ..Default::default() }.to_string();
+            if !req.override_protection {
+                // Appends land below the current data, so check that extent.
+                let existing = self.client.get_values_rendered(&req.spreadsheet_id, std::slice::from_ref(&sheet), "FORMATTED_VALUE").await
+                    .map_err(SpreadsheetError::Api)?;
+                let used_rows = existing.first().map(|(_, v)| v.len()).unwrap_or(0) as u32;
+                let append_at = A1Range { sheet: Some(req.sheet.clone()), ..Default::default() }.cell_at(used_rows, 0).to_string();
+                ensure_unprotected(&self.client, &req.spreadsheet_id, &[(append_at.as_str(), &rows)]).await?;
+            }
+            let mut chunks = 0;
+            let mut operation_ids = Vec::new();
+            let mut rows = rows.into_iter().peekable();
+            while rows.peek().is_some() {
+                let chunk: Vec<Vec<String>> = rows.by_ref().take(chunk_rows.max(1)).collect();
+                let backup = append_backup(&self.client, sheet.clone(), &chunk);
+                let (_, appended) = self.client.append_sheet_data(&req.spreadsheet_id, &sheet, chunk).await
+                    .map_err(SpreadsheetError::Api)?;
+                if let Some(backup) = backup {
+                    let backups = vec![crate::journal::RangeBackup { range: appended, ..backup }];
+                    operation_ids.extend(journal_write(&self.client, "import_csv", &req.spreadsheet_id, backups));
+                }
+                chunks += 1;
+            }
+            return Ok(ImportCsvResponse { rows_written, columns, chunks, operation_ids, dry_run: None });
+        }
+
+        let start = A1Range::parse(req.start_cell.as_deref().unwrap_or("A1")).map_err(SpreadsheetError::InvalidRequest)?;
+        let origin = (start.start_row.unwrap_or(0), start.start_col.unwrap_or(0));
+        let mut operation_ids: Vec<String> = self.prepare_sheet(&req, origin, rows_written, columns).await?.into_iter().collect();
+        let writes = chunk_writes(&req.sheet, origin, rows, chunk_rows);
+        let chunks = writes.len();
+        for write in writes {
+            let written = BatchSetSheetDataHandler { client: self.client.clone() }
+                .handle(BatchSetSheetDataRequest {
+                    spreadsheet_id: req.spreadsheet_id.clone(),
+                    updates: vec![write],
+                    override_protection: req.override_protection,
+                    expected: Default::default(),
+                    dry_run: false,
+                })
+                .await?;
+            operation_ids.extend(written.operation_id);
+        }
+        Ok(ImportCsvResponse { rows_written, columns, chunks, operation_ids, dry_run: None })
+    }
+}
+
+// ===== Export Types =====
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Json, // records keyed by header
+    Markdown,
+    Xlsx, // whole spreadsheet, via Drive
+    Pdf,
+    Ods,
+}
+
+impl ExportFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Tsv => "text/tab-separated-values",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Markdown => "text/markdown",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ExportFormat::Pdf => "application/pdf",
+            ExportFormat::Ods => "application/vnd.oasis.opendocument.spreadsheet",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Ods => "ods",
+        }
+    }
+
+    /// Whether the format is produced by Drive for the whole spreadsheet.
+    pub fn is_binary(self) -> bool {
+        matches!(self, ExportFormat::Xlsx | ExportFormat::Pdf | ExportFormat::Ods)
+    }
+}
+
+/// Renders range values in one of the text formats.
+pub fn render_values(values: &[Vec<String>], format: ExportFormat, header_row: Option<usize>) -> Result<String, String> {
+    match format {
+        ExportFormat::Csv => Ok(crate::delimited::write_delimited(values, ',')),
+        ExportFormat::Tsv => Ok(crate::delimited::write_delimited(values, '\t')),
+        ExportFormat::Json => {
+            let table = crate::table::Table::from_values(values, header_row)?;
+            let records: Vec<serde_json::Value> = table.rows.iter().map(|r| serde_json::Value::Object(table.record(r))).collect();
+            serde_json::to_string_pretty(&records).map_err(|e| e.to_string())
+        }
+        ExportFormat::Markdown => Ok(crate::table::Table::from_values(values, header_row)?.to_markdown()),
+        other => Err(format!("{:?} is not a text format", other)),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct ExportRangeRequest {
+    pub spreadsheet_id: String,
+    pub range: Option<String>, // required for text formats; must be omitted for xlsx/pdf/ods
+    pub format: ExportFormat,
+    pub header_row: Option<usize>, // for json/markdown; detected when omitted
+    #[serde(default)]
+    pub as_resource: bool, // return text formats as a resource blob too
+}
+
+/// An MCP embedded resource carrying base64 content.
+#[derive(serde::Serialize)]
+pub struct ResourceBlob {
+    pub uri: String,
+    pub mime_type: String,
+    pub blob: String, // base64
+}
+
+#[derive(serde::Serialize)]
+pub struct ExportRangeResponse {
+    pub mime_type: String,
+    pub text: Option<String>, // inline content for text formats
+    pub resource: Option<ResourceBlob>, // binary formats, or text formats with as_resource
+}
+
+pub struct ExportRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ExportRangeHandler {
+    type Request = ExportRangeRequest;
+    type Response = ExportRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use base64::Engine;
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let mime_type = req.format.mime_type().to_string();
+        let uri = format!("gsheets://spreadsheets/{}/export.{}", req.spreadsheet_id, req.format.extension());
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        if req.format.is_binary() {
+            if req.range.is_some() {
+                return Err(SpreadsheetError::InvalidRequest(format!(
+                    "{:?} exports the whole spreadsheet; omit range", req.format
+                )));
+            }
+            let bytes = self.client.export_file(&req.spreadsheet_id, &mime_type).await
+                .map_err(SpreadsheetError::Api)?;
+            let resource = ResourceBlob { uri, mime_type: mime_type.clone(), blob: encode(&bytes) };
+            return Ok(ExportRangeResponse { mime_type, text: None, resource: Some(resource) });
+        }
+        let range = req.range.as_deref()
+            .ok_or_else(|| SpreadsheetError::InvalidRequest(format!("range is required for {:?}", req.format)))?;
+        let values = read_values(&self.client, &req.spreadsheet_id, range).await?;
+        let text = render_values(&values, req.format, req.header_row).map_err(SpreadsheetError::InvalidRequest)?;
+        let resource = req.as_resource.then(|| ResourceBlob { uri, mime_type: mime_type.clone(), blob: encode(text.as_bytes()) });
+        Ok(ExportRangeResponse { mime_type, text: Some(text), resource })
+    }
+}
+
+// ===== Upload Types =====
+
+/// MIME type of a spreadsheet file Drive can convert to a Google Sheet, by extension.
+pub fn spreadsheet_source_mime(filename: &str) -> Option<&'static str> {
+    let ext = std::path::Path::new(filename).extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "xlsx" => Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        "xlsm" => Some("application/vnd.ms-excel.sheet.macroEnabled.12"),
+        "xls" => Some("application/vnd.ms-excel"),
+        "ods" => Some("application/vnd.oasis.opendocument.spreadsheet"),
+        "csv" => Some("text/csv"),
+        "tsv" => Some("text/tab-separated-values"),
+        _ => None,
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct UploadSpreadsheetRequest {
+    pub path: Option<String>, // local file under one of files.allowed_dirs
+    pub content_base64: Option<String>, // alternatively, the file content
+    pub filename: Option<String>, // required with content_base64, for the file type
+    pub name: Option<String>, // title of the new spreadsheet; defaults to the file stem
+    pub parent_folder_id: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UploadSpreadsheetResponse {
+    pub spreadsheet_id: String,
+    pub name: String,
+    pub dry_run: Option<DryRunReport>,
+}
+
+/// Path-based uploads are limited to `files.allowed_dirs` in the config.
+pub struct UploadSpreadsheetHandler {
+    pub client: Arc<GoogleApiClient>,
+    pub config: Arc<crate::config::AppConfig>,
+}
+
+#[async_trait]
+impl McpHandler for UploadSpreadsheetHandler {
+    type Request = UploadSpreadsheetRequest;
+    type Response = UploadSpreadsheetResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use base64::Engine;
+        let (filename, content) = match (&req.path, &req.content_base64) {
+            (Some(path), None) => {
+                if self.config.files.allowed_dirs.is_empty() {
+                    return Err(SpreadsheetError::Forbidden("Uploads from local paths are disabled; set files.allowed_dirs".to_string()));
+                }
+                let allowed = crate::validation::AllowedPaths::new(&self.config.files.allowed_dirs)
+                    .map_err(|e| SpreadsheetError::Api(format!("Invalid files.allowed_dirs: {}", e)))?;
+                let path = allowed.validate_file(path).map_err(|e| SpreadsheetError::Forbidden(e.to_string()))?;
+                let content = tokio::fs::read(&path).await
+                    .map_err(|e| SpreadsheetError::Api(format!("Failed to read {}: {}", path.display(), e)))?;
+                let filename = path.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_default();
+                (filename, content)
+            }
+            (None, Some(encoded)) => {
+                let filename = req.filename.clone()
+                    .ok_or_else(|| SpreadsheetError::InvalidRequest("filename is required with content_base64".to_string()))?;
+                let content = base64::engine::general_purpose::STANDARD.decode(encoded.trim())
+                    .map_err(|e| SpreadsheetError::InvalidRequest(format!("Invalid base64 content: {}", e)))?;
+                (filename, content)
+            }
+            _ => return Err(SpreadsheetError::InvalidRequest("Specify exactly one of path or content_base64".to_string())),
+        };
+        let source_mime = spreadsheet_source_mime(&filename)
+            .ok_or_else(|| SpreadsheetError::InvalidRequest(format!("Unsupported spreadsheet file type: {}", filename)))?;
+        let name = req.name.clone().unwrap_or_else(|| {
+            std::path::Path::new(&filename).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or(filename.clone())
+        });
+        self.client.check_new_file(req.parent_folder_id.as_deref()).await.map_err(SpreadsheetError::Forbidden)?;
+        if req.dry_run {
+            let report = describe(
+                format!("Would upload {} ({} bytes, {}) as spreadsheet {:?}", filename, content.len(), source_mime, name),
+                serde_json::json!({ "name": name, "mimeType": "application/vnd.google-apps.spreadsheet", "parents": req.parent_folder_id }),
+            );
+            return Ok(UploadSpreadsheetResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let spreadsheet_id = self.client.upload_as_spreadsheet(&name, source_mime, content, req.parent_folder_id.as_deref()).await
+            .map_err(SpreadsheetError::Api)?;
+        Ok(UploadSpreadsheetResponse { spreadsheet_id, name, dry_run: None })
+    }
+}
+
+// ===== Diff Types =====
+
+/// Values of a range captured at a point in time, to diff against later.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct RangeSnapshot {
+    pub spreadsheet_id: String,
+    pub range: String, // concrete A1 range (named ranges expanded)
+    pub values: Vec<Vec<String>>,
+    pub captured_at: u64, // Unix seconds
+}
+
+#[derive(serde::Deserialize)]
+pub struct SnapshotRangeRequest {
+    pub spreadsheet_id: String,
+    pub range: String, // A1 notation or a named range
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DiffSource {
+    Range { spreadsheet_id: String, range: String },
+    Snapshot { snapshot: RangeSnapshot },
+}
+
+#[derive(serde::Deserialize)]
+pub struct DiffRangesRequest {
+    pub before: DiffSource,
+    pub after: DiffSource,
+    pub key_column: Option<String>, // header name used to match rows in the row summary
+    pub header_row: Option<usize>, // detected when omitted
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CellDiff {
+    pub cell: String, // address in the `after` range
+    pub kind: crate::diff::ChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DiffRangesResponse {
+    pub added: usize,
+    pub deleted: usize,
+    pub modified: usize,
+    pub changes: Vec<CellDiff>,
+    pub rows: Option<crate::diff::RowSummary>, // None when either side has no data
+}
+
+async fn capture_snapshot(client: &GoogleApiClient, spreadsheet_id: &str, range: &str) -> Result<RangeSnapshot, SpreadsheetError> {
+    let a1 = A1Range::parse(range).map_err(SpreadsheetError::InvalidRequest)?;
+    let (_, concrete) = client.resolve_range(spreadsheet_id, &a1).await.map_err(SpreadsheetError::Api)?;
+    let values = read_values(client, spreadsheet_id, range).await?;
+    let captured_at = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    Ok(RangeSnapshot { spreadsheet_id: spreadsheet_id.to_string(), range: concrete.to_string(), values, captured_at })
+}
+
+pub struct SnapshotRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for SnapshotRangeHandler {
+    type Request = SnapshotRangeRequest;
+    type Response = RangeSnapshot;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        capture_snapshot(&self.client, &req.spreadsheet_id, &req.range).await
+    }
+}
+
+pub struct DiffRangesHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+impl DiffRangesHandler {
+    async fn load(&self, source: DiffSource) -> Result<RangeSnapshot, SpreadsheetError> {
+        match source {
+            DiffSource::Range { spreadsheet_id, range } => {
+                self.client.check_file_access(&spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+                capture_snapshot(&self.client, &spreadsheet_id, &range).await
+            }
+            DiffSource::Snapshot { snapshot } => Ok(snapshot),
+        }
+    }
+}
+
+#[async_trait]
+impl McpHandler for DiffRangesHandler {
+    type Request = DiffRangesRequest;
+    type Response = DiffRangesResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        use crate::diff::ChangeKind;
+        let before = self.load(req.before).await?;
+        let after = self.load(req.after).await?;
+        let origin = A1Range::parse(&after.range).map_err(SpreadsheetError::InvalidRequest)?;
+        let changes: Vec<CellDiff> = crate::diff::diff_cells(&before.values, &after.values).into_iter().map(|c| CellDiff {
+            cell: origin.cell_at(c.row as u32, c.col as u32).to_string(),
+            kind: c.kind,
+            before: c.before,
+            after: c.after,
+        }).collect();
+        let count = |kind: ChangeKind| changes.iter().filter(|c| c.kind == kind).count();
+        let (added, deleted, modified) = (count(ChangeKind::Added), count(ChangeKind::Deleted), count(ChangeKind::Modified));
+        let tables = (
+            crate::table::Table::from_values(&before.values, req.header_row),
+            crate::table::Table::from_values(&after.values, req.header_row),
+        );
+        let rows = match tables {
+            (Ok(old), Ok(new)) => Some(crate::diff::summarize_rows(&old, &new, req.key_column.as_deref())
+                .map_err(SpreadsheetError::InvalidRequest)?),
+            _ => None,
+        };
+        Ok(DiffRangesResponse { added, deleted, modified, changes, rows })
+    }
+}
+
+// ===== Dry Run Types =====
+
+/// What a mutating call would do. Returned in the `dry_run` field of a response when the
+/// request set `dry_run: true`; the other response fields then keep their default values.
+#[derive(serde::Serialize, Debug, Default)]
+pub struct DryRunReport {
+    pub summary: String,
+    pub affected_cells: Option<usize>, // None when not applicable or the range is unbounded
+    pub changes: Vec<CellDiff>, // before/after values for cell writes
+    pub requests: Vec<serde_json::Value>, // API payloads that would have been sent
+}
+
+/// Drops `null` members recursively; the generated API types serialize unset fields as null.
+fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => Value::Object(map.into_iter()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| (k, strip_nulls(v)))
+            .collect()),
+        Value::Array(items) => Value::Array(items.into_iter().map(strip_nulls).collect()),
+        other => other,
+    }
+}
+
+/// Total cells covered by the `range`/`ranges` members of `value`, or `None` if there are
+/// none or any of them is unbounded.
+fn request_cells(value: &serde_json::Value) -> Option<usize> {
+    fn walk(value: &serde_json::Value, grids: &mut Vec<google_sheets4::api::GridRange>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, v) in map {
+                    let as_grid = |v: &serde_json::Value| serde_json::from_value::<google_sheets4::api::GridRange>(v.clone()).ok();
+                    match (key.as_str(), v) {
+                        ("range", _) => grids.extend(as_grid(v)),
+                        ("ranges", serde_json::Value::Array(items)) => grids.extend(items.iter().filter_map(as_grid)),
+                        _ => walk(v, grids),
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| walk(v, grids)),
+            _ => {}
+        }
+    }
+    let mut grids = Vec::new();
+    walk(value, &mut grids);
+    if grids.is_empty() {
+        return None;
+    }
+    grids.iter().map(|g| {
+        let rows = g.end_row_index? - g.start_row_index.unwrap_or(0);
+        let cols = g.end_column_index? - g.start_column_index.unwrap_or(0);
+        Some((rows.max(0) * cols.max(0)) as usize)
+    }).sum()
+}
+
+/// Describes a batchUpdate without sending it.
+pub fn dry_run_report(requests: &[google_sheets4::api::Request]) -> DryRunReport {
+    let requests: Vec<serde_json::Value> = requests.iter()
+        .map(|r| strip_nulls(serde_json::to_value(r).unwrap_or_default()))
+        .collect();
+    let kinds: Vec<&str> = requests.iter()
+        .filter_map(|r| r.as_object())
+        .flat_map(|o| o.keys().map(String::as_str))
+        .collect();
+    let affected_cells = requests.iter().map(request_cells).sum();
+    DryRunReport {
+        summary: format!("Would send batchUpdate: {}", kinds.join(", ")),
+        affected_cells,
+        changes: Vec::new(),
+        requests,
+    }
+}
+
+/// Describes value writes: the cells written and, for each that differs, its current
+/// and new value.
+async fn preview_writes(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    writes: &[(&str, &Vec<Vec<String>>)],
+) -> Result<DryRunReport, SpreadsheetError> {
+    let mut changes = Vec::new();
+    let mut affected = 0;
+    for (range, values) in writes {
+        let a1 = A1Range::parse(range).map_err(SpreadsheetError::InvalidRequest)?;
+        let (_, concrete) = client.resolve_range(spreadsheet_id, &a1).await.map_err(SpreadsheetError::Api)?;
+        let current = read_values(client, spreadsheet_id, range).await?;
+        affected += values.iter().map(|row| row.len()).sum::<usize>();
+        changes.extend(cell_diffs(&concrete, &current, values));
+    }
+    Ok(DryRunReport {
+        summary: format!("Would write {} cells in {} range(s); {} would change", affected, writes.len(), changes.len()),
+        affected_cells: Some(affected),
+        changes,
+        requests: Vec::new(),
+    })
+}
+
+/// The cells that change when `values` are written over `current`, both starting at the
+/// top-left of `range`.
+fn cell_diffs(range: &A1Range, current: &[Vec<String>], values: &[Vec<String>]) -> Vec<CellDiff> {
+    crate::diff::write_changes(current, values).into_iter().map(|c| CellDiff {
+        cell: range.cell_at(c.row as u32, c.col as u32).to_string(),
+        kind: c.kind,
+        before: c.before,
+        after: c.after,
+    }).collect()
+}
+
+/// A report for calls that are not Sheets batchUpdates (Drive, Docs, Calendar).
+fn describe(summary: String, payload: serde_json::Value) -> DryRunReport {
+    DryRunReport { summary, affected_cells: None, changes: Vec::new(), requests: vec![strip_nulls(payload)] }
+}
+
+// ===== Write Precondition Types =====
+
+/// What a caller expects a range to hold before writing to it, from an earlier read:
+/// either the values themselves or the `content_hash` returned alongside them.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectedContent {
+    Values(Vec<Vec<String>>),
+    Hash(String),
+}
+
+/// Re-reads `range` and fails with `Conflict` if it no longer matches `expected`. Blank and
+/// missing cells compare equal. The Sheets API has no conditional writes, so this narrows
+/// the window for lost updates rather than closing it.
+async fn check_expected(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    range: &str,
+    expected: &ExpectedContent,
+) -> Result<(), SpreadsheetError> {
+    let current = read_values(client, spreadsheet_id, range).await?;
+    let current_hash = crate::diff::content_hash(&current);
+    let unchanged = match expected {
+        ExpectedContent::Values(values) => crate::diff::diff_cells(values, &current).is_empty(),
+        ExpectedContent::Hash(hash) => hash.trim().eq_ignore_ascii_case(&current_hash),
+    };
+    if unchanged {
+        Ok(())
+    } else {
+        Err(SpreadsheetError::Conflict(format!(
+            "{} changed since it was read (current content_hash {}); re-read it and retry",
+            range, current_hash,
+        )))
+    }
+}
+
+// ===== Clear Range Types =====
+
+#[derive(serde::Deserialize)]
+pub struct ClearRangeRequest {
+    pub spreadsheet_id: String,
+    pub range: String, // A1 notation or a named range; values are cleared, formatting is kept
+    #[serde(default)]
+    pub override_protection: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct ClearRangeResponse {
+    pub cleared_range: String,
+    pub operation_id: Option<String>,
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct ClearRangeHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ClearRangeHandler {
+    type Request = ClearRangeRequest;
+    type Response = ClearRangeResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.client.check_file_access(&req.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        if !req.override_protection {
+            // Clearing touches every cell of the range, not just the ones holding values.
+            let spreadsheet = self.client.get_spreadsheet(&req.spreadsheet_id, PROTECTION_FIELDS).await
+                .map_err(SpreadsheetError::Api)?;
+            let grid = resolve_grid(&spreadsheet, &req.range)?;
+            check_protection(&spreadsheet, &[(req.range.clone(), grid)])?;
+        }
+        if req.dry_run {
+            let current = read_values(&self.client, &req.spreadsheet_id, &req.range).await?;
+            let blank: Vec<Vec<String>> = current.iter().map(|row| vec![String::new(); row.len()]).collect();
+            let report = preview_writes(&self.client, &req.spreadsheet_id, &[(req.range.as_str(), &blank)]).await?;
+            return Ok(ClearRangeResponse { dry_run: Some(report), ..Default::default() });
+        }
+        let backups = backup_ranges(&self.client, &req.spreadsheet_id, &[(req.range.as_str(), &Vec::new())]).await?;
+        let cleared_range = self.client.clear_values(&req.spreadsheet_id, &req.range).await
+            .map_err(SpreadsheetError::Api)?;
+        let operation_id = journal_write(&self.client, "clear_range", &req.spreadsheet_id, backups);
+        Ok(ClearRangeResponse { cleared_range, operation_id, dry_run: None })
+    }
+}
+
+// ===== Undo Journal Types =====
+
+/// Reads what each write is about to replace, formulas included, padded to the written
+/// extent. Returns nothing when the client has no journal.
+async fn backup_ranges(
+    client: &GoogleApiClient,
+    spreadsheet_id: &str,
+    writes: &[(&str, &Vec<Vec<String>>)],
+) -> Result<Vec<crate::journal::RangeBackup>, SpreadsheetError> {
+    if client.journal.is_none() {
+        return Ok(Vec::new());
+    }
+    let ranges: Vec<String> = writes.iter().map(|(range, _)| range.to_string()).collect();
+    let current = client.get_values_rendered(spreadsheet_id, &ranges, "FORMULA").await
+        .map_err(SpreadsheetError::Api)?;
+    Ok(writes.iter().zip(current).map(|((range, values), (_, before))| {
+        let cols = values.iter().map(|row| row.len()).max().unwrap_or(0);
+        crate::journal::RangeBackup {
+            range: range.to_string(),
+            before: crate::journal::pad_grid(before, values.len(), cols),
+        }
+    }).collect())
+}
+
+/// Backup for rows appended at `range`: the inserted rows held nothing before, so undo
+/// blanks them. Returns nothing when the client has no journal.
+fn append_backup(client: &GoogleApiClient, range: String, values: &[Vec<String>]) -> Option<crate::journal::RangeBackup> {
+    client.journal.as_ref()?;
+    let cols = values.iter().map(|row| row.len()).max().unwrap_or(0);
+    Some(crate::journal::RangeBackup { range, before: crate::journal::pad_grid(Vec::new(), values.len(), cols) })
+}
+
+/// Journals a completed write and returns its operation ID. The write has already
+/// happened by then, so a journal failure is logged rather than returned.
+fn journal_write(client: &GoogleApiClient, tool: &str, spreadsheet_id: &str, backups: Vec<crate::journal::RangeBackup>) -> Option<String> {
+    let journal = client.journal.as_ref()?;
+    match journal.record(tool, spreadsheet_id, backups) {
+        Ok(id) => Some(id),
+        Err(e) => {
+            tracing::warn!("Failed to journal {} on {}: {}", tool, spreadsheet_id, e);
+            None
+        }
+    }
+}
+
+fn require_journal(client: &GoogleApiClient) -> Result<&crate::journal::Journal, SpreadsheetError> {
+    client.journal.as_ref()
+        .ok_or_else(|| SpreadsheetError::Forbidden("The undo journal is disabled; set journal.path".to_string()))
+}
+
+#[derive(serde::Deserialize)]
+pub struct ListOperationsRequest {
+    pub spreadsheet_id: Option<String>, // only operations on this spreadsheet
+    pub limit: Option<usize>, // most recent first, default OPERATIONS_PAGE_SIZE
+}
+
+#[derive(serde::Serialize)]
+pub struct OperationSummary {
+    pub id: String,
+    pub timestamp: u64,
+    pub tool: String,
+    pub spreadsheet_id: String,
+    pub ranges: Vec<String>,
+    pub undone_by: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ListOperationsResponse {
+    pub operations: Vec<OperationSummary>,
+}
+
+pub const OPERATIONS_PAGE_SIZE: usize = 20;
+
+pub struct ListOperationsHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for ListOperationsHandler {
+    type Request = ListOperationsRequest;
+    type Response = ListOperationsResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        let operations = require_journal(&self.client)?.operations().map_err(SpreadsheetError::Api)?;
+        let operations = operations.into_iter().rev()
+            .filter(|op| req.spreadsheet_id.iter().all(|id| &op.spreadsheet_id == id))
+            .take(req.limit.unwrap_or(OPERATIONS_PAGE_SIZE))
+            .map(|op| OperationSummary {
+                ranges: op.ranges.into_iter().map(|b| b.range).collect(),
+                id: op.id,
+                timestamp: op.timestamp,
+                tool: op.tool,
+                spreadsheet_id: op.spreadsheet_id,
+                undone_by: op.undone_by,
+            })
+            .collect();
+        Ok(ListOperationsResponse { operations })
+    }
+}
+
+/// Restores the ranges an operation wrote to their prior contents. The restore is itself
+/// journaled, so an undo can be undone.
+#[derive(serde::Deserialize)]
+pub struct UndoOperationRequest {
+    pub operation_id: String,
+    #[serde(default)]
+    pub override_protection: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct UndoOperationResponse {
+    pub restored_ranges: Vec<String>,
+    pub updated_cells: usize,
+    pub operation_id: Option<String>, // the restore's own journal entry
+    pub dry_run: Option<DryRunReport>,
+}
+
+pub struct UndoOperationHandler {
+    pub client: Arc<GoogleApiClient>,
+}
+
+#[async_trait]
+impl McpHandler for UndoOperationHandler {
+    type Request = UndoOperationRequest;
+    type Response = UndoOperationResponse;
+    type Error = SpreadsheetError;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        let journal = require_journal(&self.client)?;
+        let operation = journal.operations().map_err(SpreadsheetError::Api)?
+            .into_iter()
+            .find(|op| op.id == req.operation_id)
+            .ok_or_else(|| SpreadsheetError::InvalidRequest(format!("Unknown operation: {}", req.operation_id)))?;
+        if let Some(undo_id) = &operation.undone_by {
+            return Err(SpreadsheetError::InvalidRequest(format!("Operation {} was already undone by {}", operation.id, undo_id)));
+        }
+        self.client.check_file_access(&operation.spreadsheet_id).await.map_err(SpreadsheetError::Forbidden)?;
+        let updates: Vec<(String, Vec<Vec<String>>)> = operation.ranges.into_iter().map(|b| (b.range, b.before)).collect();
+        let writes: Vec<(&str, &Vec<Vec<String>>)> = updates.iter().map(|(r, v)| (r.as_str(), v)).collect();
+        if !req.override_protection {
+            ensure_unprotected(&self.client, &operation.spreadsheet_id, &writes).await?;
+        }
+        let restored_ranges = updates.iter().map(|(range, _)| range.clone()).collect();
+        if req.dry_run {
+            let report = preview_writes(&self.client, &operation.spreadsheet_id, &writes).await?;
+            return Ok(UndoOperationResponse { restored_ranges, dry_run: Some(report), ..Default::default() });
+        }
+        let backups = backup_ranges(&self.client, &operation.spreadsheet_id, &writes).await?;
+        let updated_cells = self.client.batch_set_sheet_data(&operation.spreadsheet_id, &updates).await
+            .map_err(SpreadsheetError::Api)?;
+        let operation_id = journal_write(&self.client, "undo_operation", &operation.spreadsheet_id, backups);
+        journal.mark_undone(&operation.id, operation_id.clone()).map_err(SpreadsheetError::Api)?;
+        Ok(UndoOperationResponse { restored_ranges, updated_cells, operation_id, dry_run: None })
+    }
+}
+
 #[async_trait]
 pub trait McpHandler {
     type Request;
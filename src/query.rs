@@ -0,0 +1,454 @@
+use crate::table::{cells_equal, parse_date, parse_number, ColumnType, Table};
+use std::cmp::Ordering;
+
+/// A query in a small SQL-like language evaluated over a `Table`:
+///
+/// ```text
+/// SELECT col1, "Due Date" WHERE status = 'open' AND amount > 100 ORDER BY date DESC LIMIT 20 OFFSET 40
+/// ```
+///
+/// Columns are referred to by header (bare identifiers or double-quoted names), comparisons
+/// are typed by the inferred column type, and text comparisons ignore case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub columns: Option<Vec<String>>, // None for `SELECT *`
+    pub filter: Option<Expr>,
+    pub order_by: Vec<(String, bool)>, // (column, descending)
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare { column: String, op: CompareOp, value: Literal },
+    IsNull { column: String, negated: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    Like,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Quoted(String), // "Column Name"
+    Text(String),   // 'literal'
+    Number(f64),
+    Symbol(&'static str),
+    Comma,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            '*' => { tokens.push(Token::Symbol("*")); i += 1; }
+            '=' => { tokens.push(Token::Symbol("=")); i += 1; }
+            '!' if chars.get(i + 1) == Some(&'=') => { tokens.push(Token::Symbol("!=")); i += 2; }
+            '<' => match chars.get(i + 1) {
+                Some('=') => { tokens.push(Token::Symbol("<=")); i += 2; }
+                Some('>') => { tokens.push(Token::Symbol("!=")); i += 2; }
+                _ => { tokens.push(Token::Symbol("<")); i += 1; }
+            },
+            '>' => match chars.get(i + 1) {
+                Some('=') => { tokens.push(Token::Symbol(">=")); i += 2; }
+                _ => { tokens.push(Token::Symbol(">")); i += 1; }
+            },
+            '\'' | '"' | '`' => {
+                // '' (or "") inside a quoted string is an escaped quote.
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err("Unterminated quoted string".to_string()),
+                        Some(&ch) if ch == quote => {
+                            if chars.get(i + 1) == Some(&quote) {
+                                s.push(quote);
+                                i += 2;
+                            } else {
+                                i += 1;
+                                break;
+                            }
+                        }
+                        Some(&ch) => { s.push(ch); i += 1; }
+                    }
+                }
+                tokens.push(if quote == '\'' { Token::Text(s) } else { Token::Quoted(s) });
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(text.parse().map_err(|_| format!("Invalid number: {}", text))?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            other => return Err(format!("Unexpected character '{}'", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("Expected {} near {:?}", keyword, self.peek()))
+        }
+    }
+
+    fn column(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(s)) if !is_keyword(&s) => Ok(s),
+            Some(Token::Quoted(s)) => Ok(s),
+            other => Err(format!("Expected column name, found {:?}", other)),
+        }
+    }
+
+    fn count(&mut self, clause: &str) -> Result<usize, String> {
+        match self.next() {
+            Some(Token::Number(n)) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+            other => Err(format!("{} expects a non-negative integer, found {:?}", clause, other)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            left = Expr::Or(Box::new(left), Box::new(self.and_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            left = Expr::And(Box::new(left), Box::new(self.not_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, String> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not_expr()?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let inner = self.expr()?;
+            if self.next() != Some(Token::RParen) {
+                return Err("Expected ')'".to_string());
+            }
+            return Ok(inner);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let column = self.column()?;
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull { column, negated });
+        }
+        let negated = self.eat_keyword("NOT");
+        let op = match self.next() {
+            Some(Token::Symbol("=")) if !negated => CompareOp::Eq,
+            Some(Token::Symbol("!=")) if !negated => CompareOp::Ne,
+            Some(Token::Symbol("<")) if !negated => CompareOp::Lt,
+            Some(Token::Symbol("<=")) if !negated => CompareOp::Le,
+            Some(Token::Symbol(">")) if !negated => CompareOp::Gt,
+            Some(Token::Symbol(">=")) if !negated => CompareOp::Ge,
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("CONTAINS") => CompareOp::Contains,
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("LIKE") => CompareOp::Like,
+            other => return Err(format!("Expected comparison operator after {}, found {:?}", column, other)),
+        };
+        let value = match self.next() {
+            Some(Token::Text(s)) => Literal::Text(s),
+            Some(Token::Number(n)) => Literal::Number(n),
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("TRUE") => Literal::Bool(true),
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("FALSE") => Literal::Bool(false),
+            other => return Err(format!("Expected a literal after {}, found {:?}", column, other)),
+        };
+        let compare = Expr::Compare { column, op, value };
+        Ok(if negated { Expr::Not(Box::new(compare)) } else { compare })
+    }
+}
+
+fn is_keyword(s: &str) -> bool {
+    ["SELECT", "WHERE", "ORDER", "BY", "ASC", "DESC", "LIMIT", "OFFSET", "AND", "OR", "NOT", "IS", "NULL", "LIKE", "CONTAINS"]
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(s))
+}
+
+pub fn parse(input: &str) -> Result<Query, String> {
+    let mut p = Parser { tokens: tokenize(input)?, pos: 0 };
+    p.expect_keyword("SELECT")?;
+    let columns = if p.peek() == Some(&Token::Symbol("*")) {
+        p.pos += 1;
+        None
+    } else {
+        let mut columns = vec![p.column()?];
+        while p.peek() == Some(&Token::Comma) {
+            p.pos += 1;
+            columns.push(p.column()?);
+        }
+        Some(columns)
+    };
+    let filter = if p.eat_keyword("WHERE") { Some(p.expr()?) } else { None };
+    let mut order_by = Vec::new();
+    if p.eat_keyword("ORDER") {
+        p.expect_keyword("BY")?;
+        loop {
+            let column = p.column()?;
+            let descending = if p.eat_keyword("DESC") { true } else { p.eat_keyword("ASC"); false };
+            order_by.push((column, descending));
+            if p.peek() != Some(&Token::Comma) {
+                break;
+            }
+            p.pos += 1;
+        }
+    }
+    let limit = if p.eat_keyword("LIMIT") { Some(p.count("LIMIT")?) } else { None };
+    let offset = if p.eat_keyword("OFFSET") { Some(p.count("OFFSET")?) } else { None };
+    if let Some(token) = p.peek() {
+        return Err(format!("Unexpected {:?} at end of query", token));
+    }
+    Ok(Query { columns, filter, order_by, limit, offset })
+}
+
+/// Result of running a query: the selected column headers and matching rows.
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub matched: usize, // before LIMIT/OFFSET
+}
+
+impl Query {
+    pub fn execute(&self, table: &Table) -> Result<QueryResult, String> {
+        let resolve = |name: &str| table.column_index(name).ok_or_else(|| format!("Unknown column: {}", name));
+        let selected: Vec<usize> = match &self.columns {
+            Some(cols) => cols.iter().map(|c| resolve(c)).collect::<Result<_, _>>()?,
+            None => (0..table.headers.len()).collect(),
+        };
+        if let Some(filter) = &self.filter {
+            check_columns(filter, &resolve)?;
+        }
+        let order: Vec<(usize, bool)> = self.order_by.iter()
+            .map(|(c, desc)| resolve(c).map(|i| (i, *desc)))
+            .collect::<Result<_, _>>()?;
+
+        let mut rows: Vec<&Vec<String>> = table.rows.iter()
+            .filter(|row| self.filter.iter().all(|f| eval(f, table, row)))
+            .collect();
+        if !order.is_empty() {
+            rows.sort_by(|a, b| {
+                order.iter()
+                    .map(|&(col, desc)| {
+                        let ord = compare_cells(&a[col], &b[col], table.column_types[col]);
+                        if desc { ord.reverse() } else { ord }
+                    })
+                    .find(|o| *o != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+        let matched = rows.len();
+        let rows = rows.into_iter()
+            .skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|row| selected.iter().map(|&c| row[c].clone()).collect())
+            .collect();
+        Ok(QueryResult {
+            columns: selected.iter().map(|&c| table.headers[c].clone()).collect(),
+            rows,
+            matched,
+        })
+    }
+}
+
+fn check_columns(expr: &Expr, resolve: &dyn Fn(&str) -> Result<usize, String>) -> Result<(), String> {
+    match expr {
+        Expr::And(a, b) | Expr::Or(a, b) => {
+            check_columns(a, resolve)?;
+            check_columns(b, resolve)
+        }
+        Expr::Not(inner) => check_columns(inner, resolve),
+        Expr::Compare { column, .. } | Expr::IsNull { column, .. } => resolve(column).map(|_| ()),
+    }
+}
+
+fn eval(expr: &Expr, table: &Table, row: &[String]) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, table, row) && eval(b, table, row),
+        Expr::Or(a, b) => eval(a, table, row) || eval(b, table, row),
+        Expr::Not(inner) => !eval(inner, table, row),
+        Expr::IsNull { column, negated } => {
+            let col = table.column_index(column).unwrap();
+            row[col].trim().is_empty() != *negated
+        }
+        Expr::Compare { column, op, value } => {
+            let col = table.column_index(column).unwrap();
+            compare(&row[col], table.column_types[col], *op, value)
+        }
+    }
+}
+
+fn compare(cell: &str, ty: ColumnType, op: CompareOp, value: &Literal) -> bool {
+    let cell = cell.trim();
+    let literal = match value {
+        Literal::Text(s) => s.clone(),
+        Literal::Number(n) => n.to_string(),
+        Literal::Bool(b) => if *b { "TRUE".to_string() } else { "FALSE".to_string() },
+    };
+    match op {
+        CompareOp::Contains => return cell.to_lowercase().contains(&literal.to_lowercase()),
+        CompareOp::Like => return like(&cell.to_lowercase(), &literal.to_lowercase()),
+        _ => {}
+    }
+    if cell.is_empty() {
+        // SQL-style: empty cells only satisfy `!=`.
+        return op == CompareOp::Ne && !literal.is_empty();
+    }
+    let ordering = match ty {
+        ColumnType::Number => match (parse_number(cell), parse_number(&literal)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => None,
+        },
+        ColumnType::Date => match (parse_date(cell), parse_date(&literal)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => None,
+        },
+        _ => None,
+    };
+    let ordering = ordering.unwrap_or_else(|| {
+        if cells_equal(cell, &literal) || cell.eq_ignore_ascii_case(&literal) {
+            Ordering::Equal
+        } else {
+            cell.to_lowercase().cmp(&literal.to_lowercase())
+        }
+    });
+    match op {
+        CompareOp::Eq => ordering == Ordering::Equal,
+        CompareOp::Ne => ordering != Ordering::Equal,
+        CompareOp::Lt => ordering == Ordering::Less,
+        CompareOp::Le => ordering != Ordering::Greater,
+        CompareOp::Gt => ordering == Ordering::Greater,
+        CompareOp::Ge => ordering != Ordering::Less,
+        CompareOp::Contains | CompareOp::Like => unreachable!(),
+    }
+}
+
+/// Orders two cells of the same column; empty cells sort last.
+fn compare_cells(a: &str, b: &str, ty: ColumnType) -> Ordering {
+    let (a, b) = (a.trim(), b.trim());
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        _ => {}
+    }
+    let typed = match ty {
+        ColumnType::Number => match (parse_number(a), parse_number(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y),
+            _ => None,
+        },
+        ColumnType::Date => match (parse_date(a), parse_date(b)) {
+            (Some(x), Some(y)) => Some(x.cmp(&y)),
+            _ => None,
+        },
+        _ => None,
+    };
+    typed.unwrap_or_else(|| a.to_lowercase().cmp(&b.to_lowercase()))
+}
+
+/// SQL LIKE with `%` (any run) and `_` (any single character).
+fn like(text: &str, pattern: &str) -> bool {
+    let t: Vec<char> = text.chars().collect();
+    let p: Vec<char> = pattern.chars().collect();
+    let (mut ti, mut pi) = (0, 0);
+    let (mut star, mut mark) = (None, 0);
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '_' || p[pi] == t[ti]) {
+            ti += 1;
+            pi += 1;
+        } else if pi < p.len() && p[pi] == '%' {
+            star = Some(pi);
+            mark = ti;
+            pi += 1;
+        } else if let Some(s) = star {
+            pi = s + 1;
+            mark += 1;
+            ti = mark;
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '%' {
+        pi += 1;
+    }
+    pi == p.len()
+}
@@ -0,0 +1,58 @@
+use regex::{Regex, RegexBuilder};
+
+/// Matches cell contents the way the Sheets find/replace dialog does: a literal or
+/// regular-expression pattern, optionally case-sensitive and optionally required to
+/// cover the whole cell.
+pub struct CellMatcher {
+    regex: Regex,
+    is_regex: bool,
+}
+
+impl CellMatcher {
+    pub fn new(pattern: &str, is_regex: bool, match_case: bool, entire_cell: bool) -> Result<Self, String> {
+        if pattern.is_empty() {
+            return Err("Search text must not be empty".to_string());
+        }
+        let body = if is_regex { pattern.to_string() } else { regex::escape(pattern) };
+        let body = if entire_cell { format!("^(?:{})$", body) } else { body };
+        let regex = RegexBuilder::new(&body)
+            .case_insensitive(!match_case)
+            .build()
+            .map_err(|e| format!("Invalid regular expression: {}", e))?;
+        Ok(CellMatcher { regex, is_regex })
+    }
+
+    /// Number of non-overlapping occurrences in `cell`.
+    pub fn count(&self, cell: &str) -> usize {
+        self.regex.find_iter(cell).count()
+    }
+
+    pub fn is_match(&self, cell: &str) -> bool {
+        self.regex.is_match(cell)
+    }
+
+    /// The cell text after replacing every occurrence. In regex mode `$1` refers to
+    /// capture groups; in literal mode the replacement is inserted as-is.
+    pub fn replace(&self, cell: &str, replacement: &str) -> String {
+        if self.is_regex {
+            self.regex.replace_all(cell, replacement).into_owned()
+        } else {
+            self.regex.replace_all(cell, regex::NoExpand(replacement)).into_owned()
+        }
+    }
+}
+
+/// Whether a cell, as read with the `FORMULA` render option, holds a formula.
+pub fn is_formula(cell: &str) -> bool {
+    cell.starts_with('=')
+}
+
+/// Positions `(row, col)` within `values` of the cells the matcher accepts, in row-major
+/// order. Blank cells never match; formula cells are skipped unless `include_formulas`.
+pub fn matching_cells(matcher: &CellMatcher, values: &[Vec<String>], include_formulas: bool) -> Vec<(usize, usize)> {
+    values.iter().enumerate().flat_map(|(r, row)| {
+        row.iter().enumerate()
+            .filter(|(_, cell)| !cell.is_empty() && (include_formulas || !is_formula(cell)) && matcher.is_match(cell))
+            .map(move |(c, _)| (r, c))
+    }).collect()
+}
@@ -7,21 +7,24 @@ use crate::models::{McpHandler, ListSpreadsheetsHandler, ListSpreadsheetsRequest
     GetDocumentHandler, GetDocumentRequest, CreateDocumentHandler, CreateDocumentRequest,
     AppendTextHandler, AppendTextRequest, ListDocsHandler, ListDocsRequest, GetCalendarEventHandler, GetCalendarEventRequest, CreateCalendarEventHandler, CreateCalendarEventRequest, ListCalendarEventsHandler, ListCalendarEventsRequest};
 use crate::google_api_client::GoogleApiClient;
+use crate::config::AppConfig;
 
 // Simulated SEE event loop for demonstration
-pub async fn see_server_main(client: Arc<GoogleApiClient>) {
+pub async fn see_server_main(client: Arc<GoogleApiClient>, config: Arc<AppConfig>) {
     // In a real SEE server, this would be event-driven
     // Here, we just simulate two events
-    let handler = ListSpreadsheetsHandler { client: client.clone() };
+    // Every event dispatches through the policy, as the HTTP and stdio servers do.
+    let policy = crate::tools::ToolPolicy::from_config(&config.tools);
+    let handler = policy.guard("list_spreadsheets", ListSpreadsheetsHandler { client: client.clone() });
     let result = handler.handle(ListSpreadsheetsRequest).await;
     println!("SEE event: list result: {:?}", result);
 
-    let handler = CreateSpreadsheetHandler { client: client.clone() };
-    let req = CreateSpreadsheetRequest { title: "SEE Sheet".to_string() };
+    let handler = policy.guard("create_spreadsheet", CreateSpreadsheetHandler { client: client.clone() });
+    let req = CreateSpreadsheetRequest { title: "SEE Sheet".to_string(), dry_run: false };
     let result = handler.handle(req).await;
     println!("SEE event: create result: {:?}", result);
 
-    let handler = GetSheetDataHandler { client: client.clone() };
+    let handler = policy.guard("get_sheet_data", GetSheetDataHandler { client: client.clone() });
     let req = GetSheetDataRequest {
         spreadsheet_id: "SEE_ID".to_string(),
         range: "Sheet1!A1:B2".to_string(),
@@ -29,16 +32,19 @@ pub async fn see_server_main(client: Arc<GoogleApiClient>) {
     let result = handler.handle(req).await;
     println!("SEE event: get result: {:?}", result);
 
-    let handler = SetSheetDataHandler { client: client.clone() };
+    let handler = policy.guard("set_sheet_data", SetSheetDataHandler { client: client.clone() });
     let req = SetSheetDataRequest {
         spreadsheet_id: "SEE_ID".to_string(),
         range: "Sheet1!A1:B2".to_string(),
         values: vec![vec!["X".to_string(), "Y".to_string()]],
+        override_protection: false,
+        expected: None,
+        dry_run: false,
     };
     let result = handler.handle(req).await;
     println!("SEE event: set result: {:?}", result);
 
-    let handler = BatchGetSheetDataHandler { client: client.clone() };
+    let handler = policy.guard("batch_get_sheet_data", BatchGetSheetDataHandler { client: client.clone() });
     let req = BatchGetSheetDataRequest {
         spreadsheet_id: "SEE_ID".to_string(),
         ranges: vec!["Sheet1!A1:B2".to_string(), "Sheet2!A1:A2".to_string()],
@@ -46,101 +52,98 @@ pub async fn see_server_main(client: Arc<GoogleApiClient>) {
     let result = handler.handle(req).await;
     println!("SEE event: batch_get result: {:?}", result);
 
-    let handler = BatchSetSheetDataHandler { client: client.clone() };
+    let handler = policy.guard("batch_set_sheet_data", BatchSetSheetDataHandler { client: client.clone() });
     let req = BatchSetSheetDataRequest {
         spreadsheet_id: "SEE_ID".to_string(),
         updates: vec![
             ("Sheet1!A1:B2".to_string(), vec![vec!["1".to_string(), "2".to_string()]]),
             ("Sheet2!A1:A2".to_string(), vec![vec!["3".to_string()]])
         ],
+        override_protection: false,
+        expected: Default::default(),
+        dry_run: false,
     };
     let result = handler.handle(req).await;
     println!("SEE event: batch_set result: {:?}", result);
 
-    let handler = ListDriveFilesHandler { client: client.clone() };
+    let handler = policy.guard("list_drive_files", ListDriveFilesHandler { client: client.clone() });
     let result = handler.handle(ListDriveFilesRequest).await;
     println!("SEE event: drive_list result: {:?}", result);
 
-    let handler = GetDriveFileMetadataHandler { client: client.clone() };
+    let handler = policy.guard("get_drive_file_metadata", GetDriveFileMetadataHandler { client: client.clone() });
     let req = GetDriveFileMetadataRequest { file_id: "file1_id".to_string() };
     let result = handler.handle(req).await;
     println!("SEE event: drive_metadata result: {:?}", result);
 
-    let handler = ListPermissionsHandler { client: client.clone() };
+    let handler = policy.guard("list_permissions", ListPermissionsHandler { client: client.clone() });
     let req = ListPermissionsRequest { resource_id: "file1_id".to_string(), resource_type: crate::models::ResourceType::DriveFile };
     let result = handler.handle(req).await;
     println!("SEE event: permissions_list result: {:?}", result);
 
-    let handler = AddPermissionHandler { client: client.clone() };
+    let handler = policy.guard("add_permission", AddPermissionHandler { client: client.clone(), config: config.clone() });
     let req = AddPermissionRequest {
         resource_id: "file1_id".to_string(),
         resource_type: crate::models::ResourceType::DriveFile,
         email: "user3@example.com".to_string(),
         role: "writer".to_string(),
         type_: "user".to_string(),
+        send_notification: false,
+        message: None,
+        dry_run: false,
     };
     let result = handler.handle(req).await;
     println!("SEE event: permissions_add result: {:?}", result);
 
-    let handler = RemovePermissionHandler { client: client.clone() };
+    let handler = policy.guard("remove_permission", RemovePermissionHandler { client: client.clone(), config: config.clone() });
     let req = RemovePermissionRequest {
         resource_id: "file1_id".to_string(),
         resource_type: crate::models::ResourceType::DriveFile,
         permission_id: "perm_file1_id_user3@example.com_writer".to_string(),
+        dry_run: false,
     };
     let result = handler.handle(req).await;
     println!("SEE event: permissions_remove result: {:?}", result);
 
-    let handler = GetDocumentHandler { client: client.clone() };
+    let handler = policy.guard("get_document", GetDocumentHandler { client: client.clone() });
     let req = GetDocumentRequest { document_id: "SEE_DOC_ID".to_string() };
     let result = handler.handle(req).await;
     println!("SEE event: docs_get result: {:?}", result);
 
-    let handler = CreateDocumentHandler { client: client.clone() };
-    let req = CreateDocumentRequest { title: "SEE Doc".to_string() };
+    let handler = policy.guard("create_document", CreateDocumentHandler { client: client.clone() });
+    let req = CreateDocumentRequest { title: "SEE Doc".to_string(), dry_run: false };
     let result = handler.handle(req).await;
     println!("SEE event: docs_create result: {:?}", result);
 
-    let handler = AppendTextHandler { client: client.clone() };
-    let req = AppendTextRequest { document_id: "SEE_DOC_ID".to_string(), text: "Hello from SEE!".to_string() };
+    let handler = policy.guard("append_text", AppendTextHandler { client: client.clone() });
+    let req = AppendTextRequest { document_id: "SEE_DOC_ID".to_string(), text: "Hello from SEE!".to_string(), dry_run: false };
     let result = handler.handle(req).await;
     println!("SEE event: docs_append result: {:?}", result);
 
     // Simulate event: list Google Docs files
-    let handler = ListDocsHandler { client: client.clone() };
+    let handler = policy.guard("list_docs", ListDocsHandler { client: client.clone() });
     let req = ListDocsRequest {};
     let result = handler.handle(req).await;
     println!("SEE event: docs_list result: {:?}", result);
 
-        // Simulate event: Google Calendar
-        if event == "calendar_get_event" {
-            let handler = crate::models::GetCalendarEventHandler { client: api.clone() };
-            let req: crate::models::GetCalendarEventRequest = serde_json::from_str(&args).unwrap();
-            let resp = handler.handle(req).await;
-            match resp {
-                Ok(r) => println!("SEE_EVENT_RESPONSE:{}", serde_json::to_string(&r).unwrap()),
-                Err(e) => eprintln!("error: {}", e),
-            }
-            continue;
-        }
-        if event == "calendar_create_event" {
-            let handler = crate::models::CreateCalendarEventHandler { client: api.clone() };
-            let req: crate::models::CreateCalendarEventRequest = serde_json::from_str(&args).unwrap();
-            let resp = handler.handle(req).await;
-            match resp {
-                Ok(r) => println!("SEE_EVENT_RESPONSE:{}", serde_json::to_string(&r).unwrap()),
-                Err(e) => eprintln!("error: {}", e),
-            }
-            continue;
-        }
-        if event == "calendar_list_events" {
-            let handler = crate::models::ListCalendarEventsHandler { client: api.clone() };
-            let req: crate::models::ListCalendarEventsRequest = serde_json::from_str(&args).unwrap();
-            let resp = handler.handle(req).await;
-            match resp {
-                Ok(r) => println!("SEE_EVENT_RESPONSE:{}", serde_json::to_string(&r).unwrap()),
-                Err(e) => eprintln!("error: {}", e),
-            }
-            continue;
-        }
+    let handler = policy.guard("get_calendar_event", GetCalendarEventHandler { client: client.clone() });
+    let req = GetCalendarEventRequest { calendar_id: "primary".to_string(), event_id: "SEE_EVENT_ID".to_string() };
+    let result = handler.handle(req).await;
+    println!("SEE event: calendar_get_event result: {:?}", result);
+
+    let handler = policy.guard("create_calendar_event", CreateCalendarEventHandler { client: client.clone() });
+    let req = CreateCalendarEventRequest {
+        calendar_id: "primary".to_string(),
+        summary: "SEE Event".to_string(),
+        description: None,
+        start: "2024-01-01T10:00:00Z".to_string(),
+        end: "2024-01-01T11:00:00Z".to_string(),
+        dry_run: false,
+    };
+    let result = handler.handle(req).await;
+    println!("SEE event: calendar_create_event result: {:?}", result);
+
+    let handler = policy.guard("list_calendar_events", ListCalendarEventsHandler { client: client.clone() });
+    let req = ListCalendarEventsRequest { calendar_id: "primary".to_string() };
+    let result = handler.handle(req).await;
+    println!("SEE event: calendar_list_events result: {:?}", result);
 }
@@ -0,0 +1,96 @@
+use crate::config::SharingConfig;
+use std::io::Write;
+
+/// Drive roles from least to most privileged.
+const ROLES: &[&str] = &["reader", "commenter", "writer", "fileOrganizer", "organizer", "owner"];
+
+pub const DEFAULT_MAX_ROLE: &str = "writer";
+
+fn role_rank(role: &str) -> Option<usize> {
+    ROLES.iter().position(|r| *r == role)
+}
+
+pub fn validate_max_role(config: &SharingConfig) -> Result<(), String> {
+    match &config.max_role {
+        Some(role) if role_rank(role).is_none() => {
+            Err(format!("Invalid sharing.max_role: {} (expected one of {})", role, ROLES.join(", ")))
+        }
+        // Granting owner needs transferOwnership, which add_permission never sends.
+        Some(role) if role == "owner" => {
+            Err("Invalid sharing.max_role: owner (ownership transfers are not supported)".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks a permission grant against the sharing guardrails. `email` is the grantee's
+/// address for `user`/`group` grants and the domain name for `domain` grants.
+pub fn check_grant(config: &SharingConfig, email: &str, role: &str, type_: &str) -> Result<(), String> {
+    let max_role = config.max_role.as_deref().unwrap_or(DEFAULT_MAX_ROLE);
+    let rank = role_rank(role).ok_or_else(|| format!("Unknown role: {} (expected one of {})", role, ROLES.join(", ")))?;
+    let max_rank = role_rank(max_role).ok_or_else(|| format!("Invalid sharing.max_role: {}", max_role))?;
+    if rank > max_rank {
+        return Err(format!("Role {} exceeds the maximum allowed role {} (sharing.max_role)", role, max_role));
+    }
+    let domain = match type_ {
+        "user" | "group" => email.rsplit_once('@')
+            .map(|(_, domain)| domain)
+            .filter(|d| !d.is_empty())
+            .ok_or_else(|| format!("Invalid email address: {}", email))?,
+        "domain" if !config.allow_domain_sharing => {
+            return Err("Domain-wide sharing is disabled (sharing.allow_domain_sharing)".to_string());
+        }
+        "domain" => email,
+        "anyone" if !config.allow_anyone_sharing => {
+            return Err("Sharing with anyone who has the link is disabled (sharing.allow_anyone_sharing)".to_string());
+        }
+        "anyone" => return Ok(()),
+        _ => return Err(format!("Unknown permission type: {} (expected user, group, domain or anyone)", type_)),
+    };
+    let allowed = config.allowed_domains.is_empty()
+        || config.allowed_domains.iter().any(|d| d.eq_ignore_ascii_case(domain));
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("Sharing with domain {} is not allowed (sharing.allowed_domains)", domain))
+    }
+}
+
+/// One sharing change or refused attempt.
+#[derive(Debug, serde::Serialize)]
+pub struct AuditEntry<'a> {
+    pub timestamp: u64, // seconds since the Unix epoch
+    pub action: &'a str, // add_permission or remove_permission
+    pub resource_id: &'a str,
+    pub email: Option<&'a str>,
+    pub role: Option<&'a str>,
+    #[serde(rename = "type")]
+    pub type_: Option<&'a str>,
+    pub permission_id: Option<&'a str>,
+    pub outcome: String, // "ok", "denied: <reason>" or "error: <reason>"
+}
+
+/// Appends `entry` to `sharing.audit_log` as a JSON line, or logs it through tracing when
+/// no file is configured. Audit failures are logged; the change itself has already been made.
+pub fn audit(config: &SharingConfig, entry: &AuditEntry) {
+    let line = match serde_json::to_string(entry) {
+        Ok(line) => line,
+        Err(e) => {
+            tracing::warn!("Failed to encode sharing audit entry: {}", e);
+            return;
+        }
+    };
+    let Some(path) = &config.audit_log else {
+        tracing::info!(target: "audit", "{}", line);
+        return;
+    };
+    let written = std::fs::OpenOptions::new().create(true).append(true).open(path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = written {
+        tracing::warn!("Failed to write sharing audit log {}: {} ({})", path, e, line);
+    }
+}
+
+pub fn now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
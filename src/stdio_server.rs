@@ -8,21 +8,85 @@ use crate::models::{McpHandler, ListSpreadsheetsHandler, ListSpreadsheetsRequest
     AppendTextHandler, AppendTextRequest, GetCalendarEventHandler, GetCalendarEventRequest, CreateCalendarEventHandler, CreateCalendarEventRequest,
     ListCalendarEventsHandler, ListCalendarEventsRequest};
 use crate::google_api_client::GoogleApiClient;
+use crate::config::AppConfig;
 
-pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
+/// The tool a command runs, for policy checks. Most commands are named after their tool;
+/// these older ones predate the tool names.
+pub fn command_tool(command: &str) -> &str {
+    match command {
+        "list" => "list_spreadsheets",
+        "create" => "create_spreadsheet",
+        "get" => "get_sheet_data",
+        "set" => "set_sheet_data",
+        "batch_get" => "batch_get_sheet_data",
+        "batch_set" => "batch_set_sheet_data",
+        "drive_list" => "list_drive_files",
+        "drive_metadata" => "get_drive_file_metadata",
+        "permissions_list" => "list_permissions",
+        "permissions_add" => "add_permission",
+        "permissions_remove" => "remove_permission",
+        "docs_get" => "get_document",
+        "docs_create" => "create_document",
+        "docs_append" => "append_text",
+        "docs_list" => "list_docs",
+        "calendar_get_event" => "get_calendar_event",
+        "calendar_create_event" => "create_calendar_event",
+        "calendar_list_events" => "list_calendar_events",
+        "conditional_formats_list" => "list_conditional_formats",
+        "conditional_formats_add" => "add_conditional_format",
+        "conditional_formats_update" => "update_conditional_format",
+        "conditional_formats_delete" => "delete_conditional_format",
+        other => other,
+    }
+}
+
+/// Parses one `range=csv` pair of a `batch_set` command. Rows within the csv are separated
+/// by `|`, cells by `,`; quote cells that contain `,`, `|`, `;` or `=`.
+///
+/// The older `cell:csv` form is still accepted when the text before the first `:` is a
+/// single cell. A pair that reads both ways, such as `A1:x=y`, is rejected unless the
+/// `=` form names a bounded range like `A1:B2`.
+pub fn parse_batch_set_pair(pair: &str) -> Result<(String, Vec<Vec<String>>), String> {
+    use crate::a1::A1Range;
+    let current = pair.split_once('=')
+        .and_then(|(range, csv)| Some((range, csv, A1Range::parse(range).ok()?)));
+    let legacy = pair.split_once(':')
+        .filter(|(cell, _)| A1Range::parse(cell).ok().and_then(|a1| a1.dimensions()) == Some((1, 1)));
+    let (range, csv) = match (current, legacy) {
+        (Some((range, csv, _)), None) => (range, csv),
+        (Some((range, csv, a1)), Some(_)) if a1.dimensions().is_some() => (range, csv),
+        (Some(_), Some(_)) => return Err(format!("Ambiguous pair, write <range>=<csv> with a bounded range: {}", pair)),
+        (None, Some(legacy)) => legacy,
+        (None, None) => return Err(format!("Expected <range>=<csv>: {}", pair)),
+    };
+    Ok((range.to_string(), crate::delimited::parse_delimited(csv, ',', Some('|'))?))
+}
+
+pub async fn stdio_server_main(client: Arc<GoogleApiClient>, config: Arc<AppConfig>) {
     use std::io::{self, BufRead, Write};
     let stdin = io::stdin();
     let mut stdout = io::stdout();
+    let policy = crate::tools::ToolPolicy::from_config(&config.tools);
     for line in stdin.lock().lines() {
         let line = line.unwrap();
-        if line == "list" {
+        let tool = command_tool(line.split(' ').next().unwrap_or_default());
+        if crate::tools::lookup(tool).is_some() {
+            if let Err(reason) = policy.check(tool) {
+                writeln!(stdout, "error: {}", reason).unwrap();
+                stdout.flush().unwrap();
+                continue;
+            }
+        }
+        if line == "tools_list" {
+            writeln!(stdout, "{}", serde_json::to_string(&policy.allowed_tools()).unwrap()).unwrap();
+        } else if line == "list" {
             let handler = ListSpreadsheetsHandler { client: client.clone() };
             let result = handler.handle(ListSpreadsheetsRequest).await;
             writeln!(stdout, "{:?}", result).unwrap();
-        } else if line.starts_with("create ") {
-            let title = line[7..].to_string();
+        } else if let Some(title) = line.strip_prefix("create ") {
+            let title = title.to_string();
             let handler = CreateSpreadsheetHandler { client: client.clone() };
-            let req = CreateSpreadsheetRequest { title };
+            let req = CreateSpreadsheetRequest { title, dry_run: false };
             let result = handler.handle(req).await;
             writeln!(stdout, "{:?}", result).unwrap();
         } else if line.starts_with("get ") {
@@ -43,14 +107,21 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
             // set <spreadsheet_id> <range> <csv_values>
             let parts: Vec<&str> = line.splitn(4, ' ').collect();
             if parts.len() == 4 {
-                let values: Vec<Vec<String>> = parts[3]
-                    .split(';')
-                    .map(|row| row.split(',').map(|s| s.to_string()).collect())
-                    .collect();
+                // Rows are separated by `;`, cells by `,`; quote cells that contain either.
+                let values = match crate::delimited::parse_delimited(parts[3], ',', Some(';')) {
+                    Ok(values) => values,
+                    Err(e) => {
+                        writeln!(stdout, "error: {}", e).unwrap();
+                        continue;
+                    }
+                };
                 let req = SetSheetDataRequest {
                     spreadsheet_id: parts[1].to_string(),
                     range: parts[2].to_string(),
                     values,
+                    override_protection: false,
+                    expected: None,
+                    dry_run: false,
                 };
                 let handler = SetSheetDataHandler { client: client.clone() };
                 let result = handler.handle(req).await;
@@ -74,28 +145,33 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
                 writeln!(stdout, "Usage: batch_get <spreadsheet_id> <range1,range2,...>").unwrap();
             }
         } else if line.starts_with("batch_set ") {
-            // batch_set <spreadsheet_id> <range1:csv1;range2:csv2;...>
+            // batch_set <spreadsheet_id> <range1=csv1;range2=csv2;...>; see `parse_batch_set_pair`
             let parts: Vec<&str> = line.splitn(3, ' ').collect();
             if parts.len() == 3 {
-                let updates: Vec<(String, Vec<Vec<String>>)> = parts[2]
-                    .split(';')
-                    .filter_map(|pair| {
-                        let mut split = pair.splitn(2, ':');
-                        let range = split.next()?.to_string();
-                        let csv = split.next()?;
-                        let values = csv.split('|').map(|row| row.split(',').map(|s| s.to_string()).collect()).collect();
-                        Some((range, values))
-                    })
-                    .collect();
+                let updates = crate::delimited::split_unquoted(parts[2], ';')
+                    .into_iter()
+                    .filter(|pair| !pair.trim().is_empty())
+                    .map(parse_batch_set_pair)
+                    .collect::<Result<Vec<_>, String>>();
+                let updates = match updates {
+                    Ok(updates) => updates,
+                    Err(e) => {
+                        writeln!(stdout, "error: {}", e).unwrap();
+                        continue;
+                    }
+                };
                 let req = BatchSetSheetDataRequest {
                     spreadsheet_id: parts[1].to_string(),
                     updates,
+                    override_protection: false,
+                    expected: Default::default(),
+                    dry_run: false,
                 };
                 let handler = BatchSetSheetDataHandler { client: client.clone() };
                 let result = handler.handle(req).await;
                 writeln!(stdout, "{:?}", result).unwrap();
             } else {
-                writeln!(stdout, "Usage: batch_set <spreadsheet_id> <range1:csv1;range2:csv2;...>").unwrap();
+                writeln!(stdout, "Usage: batch_set <spreadsheet_id> <range1=csv1;range2=csv2;...>").unwrap();
             }
         } else if line == "drive_list" {
             let handler = ListDriveFilesHandler { client: client.clone() };
@@ -146,8 +222,11 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
                     email: parts[3].to_string(),
                     role: parts[4].to_string(),
                     type_: parts[5].to_string(),
+                    send_notification: false,
+                    message: None,
+                    dry_run: false,
                 };
-                let handler = AddPermissionHandler { client: client.clone() };
+                let handler = AddPermissionHandler { client: client.clone(), config: config.clone() };
                 let result = handler.handle(req).await;
                 writeln!(stdout, "{:?}", result).unwrap();
             } else {
@@ -168,8 +247,9 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
                     resource_id: parts[2].to_string(),
                     resource_type,
                     permission_id: parts[3].to_string(),
+                    dry_run: false,
                 };
-                let handler = RemovePermissionHandler { client: client.clone() };
+                let handler = RemovePermissionHandler { client: client.clone(), config: config.clone() };
                 let result = handler.handle(req).await;
                 writeln!(stdout, "{:?}", result).unwrap();
             } else {
@@ -190,7 +270,7 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
             // docs_create <title>
             let parts: Vec<&str> = line.splitn(2, ' ').collect();
             if parts.len() == 2 {
-                let req = CreateDocumentRequest { title: parts[1].to_string() };
+                let req = CreateDocumentRequest { title: parts[1].to_string(), dry_run: false };
                 let handler = CreateDocumentHandler { client: client.clone() };
                 let result = handler.handle(req).await;
                 writeln!(stdout, "{:?}", result).unwrap();
@@ -204,6 +284,7 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
                 let req = AppendTextRequest {
                     document_id: parts[1].to_string(),
                     text: parts[2].to_string(),
+                    dry_run: false,
                 };
                 let handler = AppendTextHandler { client: client.clone() };
                 let result = handler.handle(req).await;
@@ -212,51 +293,560 @@ pub async fn stdio_server_main(client: Arc<GoogleApiClient>) {
                 writeln!(stdout, "Usage: docs_append <document_id> <text>").unwrap();
             }
         } else if line == "docs_list" {
-            let handler = crate::models::ListDocsHandler;
+            let handler = crate::models::ListDocsHandler { client: client.clone() };
             let req = crate::models::ListDocsRequest {};
-            let resp = handler.handle(req, &client).await;
+            let resp = handler.handle(req).await;
             match resp {
                 Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
-                Err(e) => writeln!(stdout, "error: {}", e).unwrap(),
+                Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
             }
         // Google Calendar commands
         } else if line.starts_with("calendar_get_event ") {
             let parts: Vec<&str> = line.splitn(3, ' ').collect();
             if parts.len() == 3 {
                 let req = GetCalendarEventRequest {
+                    calendar_id: parts[1].to_string(),
                     event_id: parts[2].to_string(),
                 };
                 let handler = GetCalendarEventHandler { client: client.clone() };
                 let result = handler.handle(req).await;
                 writeln!(stdout, "{:?}", result).unwrap();
             } else {
-                writeln!(stdout, "Usage: calendar_get_event <event_id>").unwrap();
+                writeln!(stdout, "Usage: calendar_get_event <calendar_id> <event_id>").unwrap();
             }
-        } else if line.starts_with("calendar_create_event ") {
-            let parts: Vec<&str> = line.splitn(3, ' ').collect();
-            if parts.len() == 3 {
-                let req = CreateCalendarEventRequest {
-                    calendar_id: parts[1].to_string(),
-                    event: serde_json::from_str(&parts[2]).unwrap(),
-                };
-                let handler = CreateCalendarEventHandler { client: client.clone() };
-                let result = handler.handle(req).await;
-                writeln!(stdout, "{:?}", result).unwrap();
-            } else {
-                writeln!(stdout, "Usage: calendar_create_event <calendar_id> <event_json>").unwrap();
+        } else if let Some(json) = line.strip_prefix("calendar_create_event ") {
+            // calendar_create_event <request_json>
+            match serde_json::from_str::<CreateCalendarEventRequest>(json) {
+                Ok(req) => {
+                    let handler = CreateCalendarEventHandler { client: client.clone() };
+                    let result = handler.handle(req).await;
+                    writeln!(stdout, "{:?}", result).unwrap();
+                }
+                Err(e) => writeln!(stdout, "Usage: calendar_create_event <request_json> ({})", e).unwrap(),
             }
         } else if line.starts_with("calendar_list_events ") {
-            let parts: Vec<&str> = line.splitn(3, ' ').collect();
-            if parts.len() == 3 {
-                let req = ListCalendarEventsRequest {
-                    calendar_id: parts[1].to_string(),
-                    time_min: parts[2].to_string(),
-                };
-                let handler = ListCalendarEventsHandler { client: client.clone() };
-                let result = handler.handle(req).await;
-                writeln!(stdout, "{:?}", result).unwrap();
-            } else {
-                writeln!(stdout, "Usage: calendar_list_events <calendar_id> <time_min>").unwrap();
+            let parts: Vec<&str> = line.splitn(2, ' ').collect();
+            let req = ListCalendarEventsRequest {
+                calendar_id: parts[1].to_string(),
+            };
+            let handler = ListCalendarEventsHandler { client: client.clone() };
+            let result = handler.handle(req).await;
+            writeln!(stdout, "{:?}", result).unwrap();
+        } else if let Some(json) = line.strip_prefix("conditional_formats_list ") {
+            // conditional_formats_list <request_json>
+            match serde_json::from_str::<crate::models::ListConditionalFormatsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ListConditionalFormatsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: conditional_formats_list <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("conditional_formats_add ") {
+            // conditional_formats_add <request_json>
+            match serde_json::from_str::<crate::models::AddConditionalFormatRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::AddConditionalFormatHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: conditional_formats_add <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("conditional_formats_update ") {
+            // conditional_formats_update <request_json>
+            match serde_json::from_str::<crate::models::UpdateConditionalFormatRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UpdateConditionalFormatHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: conditional_formats_update <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("conditional_formats_delete ") {
+            // conditional_formats_delete <request_json>
+            match serde_json::from_str::<crate::models::DeleteConditionalFormatRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::DeleteConditionalFormatHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: conditional_formats_delete <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("set_data_validation ") {
+            // set_data_validation <request_json>
+            match serde_json::from_str::<crate::models::SetDataValidationRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SetDataValidationHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: set_data_validation <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("clear_data_validation ") {
+            // clear_data_validation <request_json>
+            match serde_json::from_str::<crate::models::ClearDataValidationRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ClearDataValidationHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: clear_data_validation <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("list_named_ranges ") {
+            // list_named_ranges <request_json>
+            match serde_json::from_str::<crate::models::ListNamedRangesRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ListNamedRangesHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: list_named_ranges <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("create_named_range ") {
+            // create_named_range <request_json>
+            match serde_json::from_str::<crate::models::CreateNamedRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::CreateNamedRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: create_named_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("update_named_range ") {
+            // update_named_range <request_json>
+            match serde_json::from_str::<crate::models::UpdateNamedRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UpdateNamedRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: update_named_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("delete_named_range ") {
+            // delete_named_range <request_json>
+            match serde_json::from_str::<crate::models::DeleteNamedRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::DeleteNamedRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: delete_named_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("list_protected_ranges ") {
+            // list_protected_ranges <request_json>
+            match serde_json::from_str::<crate::models::ListProtectedRangesRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ListProtectedRangesHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: list_protected_ranges <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("add_protected_range ") {
+            // add_protected_range <request_json>
+            match serde_json::from_str::<crate::models::AddProtectedRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::AddProtectedRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: add_protected_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("update_protected_range ") {
+            // update_protected_range <request_json>
+            match serde_json::from_str::<crate::models::UpdateProtectedRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UpdateProtectedRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: update_protected_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("remove_protected_range ") {
+            // remove_protected_range <request_json>
+            match serde_json::from_str::<crate::models::RemoveProtectedRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::RemoveProtectedRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: remove_protected_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("create_chart ") {
+            // create_chart <request_json>
+            match serde_json::from_str::<crate::models::CreateChartRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::CreateChartHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: create_chart <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("update_chart ") {
+            // update_chart <request_json>
+            match serde_json::from_str::<crate::models::UpdateChartRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UpdateChartHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: update_chart <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("delete_chart ") {
+            // delete_chart <request_json>
+            match serde_json::from_str::<crate::models::DeleteChartRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::DeleteChartHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: delete_chart <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("sheets_batch_update ") {
+            // sheets_batch_update <request_json>
+            match serde_json::from_str::<crate::models::SheetsBatchUpdateRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SheetsBatchUpdateHandler { client: client.clone(), config: config.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: sheets_batch_update <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("read_table ") {
+            // read_table <request_json>
+            match serde_json::from_str::<crate::models::ReadTableRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ReadTableHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: read_table <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("upsert_rows ") {
+            // upsert_rows <request_json>
+            match serde_json::from_str::<crate::models::UpsertRowsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UpsertRowsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: upsert_rows <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("query_sheet ") {
+            // query_sheet <request_json>
+            match serde_json::from_str::<crate::models::QuerySheetRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::QuerySheetHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: query_sheet <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("find_replace ") {
+            // find_replace <request_json>
+            match serde_json::from_str::<crate::models::FindReplaceRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::FindReplaceHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: find_replace <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("search_cells ") {
+            // search_cells <request_json>
+            match serde_json::from_str::<crate::models::SearchCellsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SearchCellsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: search_cells <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("sort_range ") {
+            // sort_range <request_json>
+            match serde_json::from_str::<crate::models::SortRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SortRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: sort_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("set_basic_filter ") {
+            // set_basic_filter <request_json>
+            match serde_json::from_str::<crate::models::SetBasicFilterRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SetBasicFilterHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: set_basic_filter <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("clear_basic_filter ") {
+            // clear_basic_filter <request_json>
+            match serde_json::from_str::<crate::models::ClearBasicFilterRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ClearBasicFilterHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: clear_basic_filter <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("create_filter_view ") {
+            // create_filter_view <request_json>
+            match serde_json::from_str::<crate::models::CreateFilterViewRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::CreateFilterViewHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: create_filter_view <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("delete_filter_view ") {
+            // delete_filter_view <request_json>
+            match serde_json::from_str::<crate::models::DeleteFilterViewRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::DeleteFilterViewHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: delete_filter_view <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("get_cells ") {
+            // get_cells <request_json>
+            match serde_json::from_str::<crate::models::GetCellsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::GetCellsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: get_cells <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("set_note ") {
+            // set_note <request_json>
+            match serde_json::from_str::<crate::models::SetNoteRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SetNoteHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: set_note <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("clear_notes ") {
+            // clear_notes <request_json>
+            match serde_json::from_str::<crate::models::ClearNotesRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ClearNotesHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: clear_notes <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("set_hyperlink ") {
+            // set_hyperlink <request_json>
+            match serde_json::from_str::<crate::models::SetHyperlinkRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SetHyperlinkHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: set_hyperlink <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("merge_cells ") {
+            // merge_cells <request_json>
+            match serde_json::from_str::<crate::models::MergeCellsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::MergeCellsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: merge_cells <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("unmerge_cells ") {
+            // unmerge_cells <request_json>
+            match serde_json::from_str::<crate::models::UnmergeCellsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UnmergeCellsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: unmerge_cells <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("set_frozen ") {
+            // set_frozen <request_json>
+            match serde_json::from_str::<crate::models::SetFrozenRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SetFrozenHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: set_frozen <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("import_csv ") {
+            // import_csv <request_json>
+            match serde_json::from_str::<crate::models::ImportCsvRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ImportCsvHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: import_csv <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("export_range ") {
+            // export_range <request_json>
+            match serde_json::from_str::<crate::models::ExportRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ExportRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: export_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("upload_spreadsheet ") {
+            // upload_spreadsheet <request_json>
+            match serde_json::from_str::<crate::models::UploadSpreadsheetRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UploadSpreadsheetHandler { client: client.clone(), config: config.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: upload_spreadsheet <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("snapshot_range ") {
+            // snapshot_range <request_json>
+            match serde_json::from_str::<crate::models::SnapshotRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::SnapshotRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: snapshot_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("diff_ranges ") {
+            // diff_ranges <request_json>
+            match serde_json::from_str::<crate::models::DiffRangesRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::DiffRangesHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: diff_ranges <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("clear_range ") {
+            // clear_range <request_json>
+            match serde_json::from_str::<crate::models::ClearRangeRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ClearRangeHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: clear_range <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("list_operations ") {
+            // list_operations <request_json>
+            match serde_json::from_str::<crate::models::ListOperationsRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::ListOperationsHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: list_operations <request_json> ({})", e).unwrap(),
+            }
+        } else if let Some(json) = line.strip_prefix("undo_operation ") {
+            // undo_operation <request_json>
+            match serde_json::from_str::<crate::models::UndoOperationRequest>(json) {
+                Ok(req) => {
+                    let handler = crate::models::UndoOperationHandler { client: client.clone() };
+                    match handler.handle(req).await {
+                        Ok(r) => writeln!(stdout, "{}", serde_json::to_string(&r).unwrap()).unwrap(),
+                        Err(e) => writeln!(stdout, "error: {:?}", e).unwrap(),
+                    }
+                }
+                Err(e) => writeln!(stdout, "Usage: undo_operation <request_json> ({})", e).unwrap(),
             }
         } else {
             writeln!(stdout, "Unknown command").unwrap();
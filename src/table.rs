@@ -0,0 +1,295 @@
+use serde_json::Value;
+
+/// Value type inferred for a table column from its non-empty cells.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Number,
+    Boolean,
+    Date,
+    Text,
+    Empty,
+}
+
+/// A 2-D grid split into a header row and data rows.
+pub struct Table {
+    pub header_row: usize, // index within the source grid
+    pub headers: Vec<String>,
+    pub column_types: Vec<ColumnType>,
+    pub rows: Vec<Vec<String>>, // padded to `headers.len()`, trailing blank rows removed
+}
+
+impl Table {
+    /// Builds a table from a grid. When `header_row` is `None` the header row is detected.
+    pub fn from_values(values: &[Vec<String>], header_row: Option<usize>) -> Result<Self, String> {
+        let header_row = match header_row {
+            Some(r) if r < values.len() => r,
+            Some(r) => return Err(format!("Header row {} is outside the range ({} rows)", r, values.len())),
+            None => detect_header_row(values).ok_or_else(|| "Range contains no data".to_string())?,
+        };
+        let width = values[header_row..].iter().map(|r| r.len()).max().unwrap_or(0);
+        let headers = normalize_headers(&values[header_row], width);
+        let mut rows: Vec<Vec<String>> = values[header_row + 1..].iter().map(|r| {
+            let mut row = r.clone();
+            row.resize(width, String::new());
+            row
+        }).collect();
+        while rows.last().is_some_and(|r| is_blank(r)) {
+            rows.pop();
+        }
+        let column_types = (0..width).map(|c| infer_column_type(rows.iter().map(|r| r[c].as_str()))).collect();
+        Ok(Table { header_row, headers, column_types, rows })
+    }
+
+    /// Index of the column with the given header (exact match first, then case-insensitive).
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|h| h == name)
+            .or_else(|| self.headers.iter().position(|h| h.eq_ignore_ascii_case(name)))
+    }
+
+    /// Renders the table as a GitHub-flavoured Markdown table. Pipes are escaped and line
+    /// breaks inside cells become `<br>`.
+    pub fn to_markdown(&self) -> String {
+        let escape = |s: &str| s.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>");
+        let line = |cells: &[String]| format!("| {} |\n", cells.iter().map(|c| escape(c)).collect::<Vec<_>>().join(" | "));
+        let mut out = line(&self.headers);
+        out.push_str(&format!("|{}\n", " --- |".repeat(self.headers.len())));
+        for row in &self.rows {
+            out.push_str(&line(row));
+        }
+        out
+    }
+
+    /// One row as a JSON object keyed by header, with values typed per column.
+    pub fn record(&self, row: &[String]) -> serde_json::Map<String, Value> {
+        self.headers.iter().zip(row).zip(&self.column_types)
+            .map(|((h, cell), ty)| (h.clone(), typed_value(cell, *ty)))
+            .collect()
+    }
+}
+
+pub fn is_blank(row: &[String]) -> bool {
+    row.iter().all(|c| c.trim().is_empty())
+}
+
+/// Picks the first non-blank row among the first few whose cells are all distinct,
+/// non-numeric labels; falls back to the first non-blank row.
+pub fn detect_header_row(values: &[Vec<String>]) -> Option<usize> {
+    let first = values.iter().position(|r| !is_blank(r))?;
+    let looks_like_header = |row: &Vec<String>| {
+        let labels: Vec<&str> = row.iter().map(|c| c.trim()).filter(|c| !c.is_empty()).collect();
+        let distinct: std::collections::HashSet<&str> = labels.iter().copied().collect();
+        !labels.is_empty()
+            && distinct.len() == labels.len()
+            && labels.iter().all(|c| parse_number(c).is_none() && parse_date(c).is_none())
+    };
+    values.iter().enumerate().skip(first).take(10)
+        .find(|(i, row)| looks_like_header(row) && values.get(i + 1).is_some_and(|next| !is_blank(next)))
+        .map(|(i, _)| i)
+        .or(Some(first))
+}
+
+/// Trims header cells, names empty ones after their column letter and de-duplicates.
+fn normalize_headers(row: &[String], width: usize) -> Vec<String> {
+    let mut seen = std::collections::HashMap::new();
+    (0..width).map(|c| {
+        let raw = row.get(c).map(|h| h.trim()).unwrap_or_default();
+        let base = if raw.is_empty() { format!("column_{}", crate::a1::column_to_letters(c as u32)) } else { raw.to_string() };
+        let count = seen.entry(base.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 { base } else { format!("{}_{}", base, count) }
+    }).collect()
+}
+
+pub fn infer_column_type<'a>(cells: impl Iterator<Item = &'a str>) -> ColumnType {
+    let mut inferred = ColumnType::Empty;
+    for cell in cells.map(str::trim).filter(|c| !c.is_empty()) {
+        let ty = if parse_number(cell).is_some() {
+            ColumnType::Number
+        } else if parse_bool(cell).is_some() {
+            ColumnType::Boolean
+        } else if parse_date(cell).is_some() {
+            ColumnType::Date
+        } else {
+            return ColumnType::Text;
+        };
+        if inferred == ColumnType::Empty {
+            inferred = ty;
+        } else if inferred != ty {
+            return ColumnType::Text;
+        }
+    }
+    inferred
+}
+
+/// Converts a cell to JSON according to its column type; empty cells become `null`.
+pub fn typed_value(cell: &str, ty: ColumnType) -> Value {
+    let trimmed = cell.trim();
+    if trimmed.is_empty() {
+        return Value::Null;
+    }
+    match ty {
+        ColumnType::Number => parse_number(trimmed)
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(cell.to_string())),
+        ColumnType::Boolean => parse_bool(trimmed).map(Value::Bool).unwrap_or_else(|| Value::String(cell.to_string())),
+        ColumnType::Date => parse_date(trimmed).map(Value::String).unwrap_or_else(|| Value::String(cell.to_string())),
+        ColumnType::Text | ColumnType::Empty => Value::String(cell.to_string()),
+    }
+}
+
+/// Parses numbers as Sheets formats them: thousands separators, a leading currency
+/// sign and a trailing percent sign are accepted.
+pub fn parse_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let (s, percent) = match s.strip_suffix('%') {
+        Some(rest) => (rest, true),
+        None => (s, false),
+    };
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let s = s.trim_start_matches(['$', '€', '£']);
+    let cleaned: String = s.chars().filter(|&c| c != ',').collect();
+    if cleaned.is_empty() || !cleaned.chars().all(|c| c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '-' || c == '+') {
+        return None;
+    }
+    let mut value: f64 = cleaned.parse().ok()?;
+    if negative {
+        value = -value;
+    }
+    if percent {
+        value /= 100.0;
+    }
+    Some(value)
+}
+
+pub fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim().to_ascii_uppercase().as_str() {
+        "TRUE" => Some(true),
+        "FALSE" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses `YYYY-MM-DD` or `M/D/YYYY` dates into ISO `YYYY-MM-DD`.
+pub fn parse_date(s: &str) -> Option<String> {
+    let s = s.trim();
+    let parts: Vec<&str>;
+    let (y, m, d) = if s.contains('-') {
+        parts = s.split('-').collect();
+        if parts.len() != 3 || parts[0].len() != 4 {
+            return None;
+        }
+        (parts[0], parts[1], parts[2])
+    } else if s.contains('/') {
+        parts = s.split('/').collect();
+        if parts.len() != 3 || parts[2].len() != 4 {
+            return None;
+        }
+        (parts[2], parts[0], parts[1])
+    } else {
+        return None;
+    };
+    let y: u32 = y.parse().ok()?;
+    let m: u32 = m.parse().ok()?;
+    let d: u32 = d.parse().ok()?;
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    Some(format!("{:04}-{:02}-{:02}", y, m, d))
+}
+
+/// Renders a JSON value as the text a user would type into a cell.
+pub fn cell_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => if *b { "TRUE".to_string() } else { "FALSE".to_string() },
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", f as i64),
+            _ => n.to_string(),
+        },
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Loose equality used when deciding whether a cell needs rewriting: `1,000` equals `1000`
+/// and `true` equals `TRUE`.
+pub fn cells_equal(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim(), b.trim());
+    if a == b {
+        return true;
+    }
+    if let (Some(x), Some(y)) = (parse_number(a), parse_number(b)) {
+        return x == y;
+    }
+    matches!((parse_bool(a), parse_bool(b)), (Some(x), Some(y)) if x == y)
+}
+
+/// Changes needed to upsert records into a table.
+#[derive(Debug, Default, PartialEq)]
+pub struct UpsertPlan {
+    /// (data row index, first column, values) — one per run of adjacent changed cells, so
+    /// unchanged cells (and any formulas in them) are never rewritten.
+    pub updates: Vec<(usize, usize, Vec<String>)>,
+    pub appends: Vec<Vec<String>>,
+    pub updated: usize, // existing rows with at least one change
+    pub unchanged: usize,
+}
+
+/// Matches `records` to table rows by `key_column` and computes the minimal writes:
+/// for existing keys each run of changed cells, for new keys a full row to append.
+pub fn plan_upsert(table: &Table, key_column: &str, records: &[serde_json::Map<String, Value>]) -> Result<UpsertPlan, String> {
+    let key_col = table.column_index(key_column).ok_or_else(|| format!("Unknown key column: {}", key_column))?;
+    let mut index = std::collections::HashMap::new();
+    for (i, row) in table.rows.iter().enumerate() {
+        let key = row[key_col].trim();
+        if !key.is_empty() {
+            index.entry(key.to_string()).or_insert(i);
+        }
+    }
+    let mut plan = UpsertPlan::default();
+    let mut seen = std::collections::HashSet::new();
+    for (n, record) in records.iter().enumerate() {
+        let mut cells = Vec::with_capacity(record.len());
+        for (field, value) in record {
+            let col = table.column_index(field).ok_or_else(|| format!("Record {}: unknown column {}", n, field))?;
+            cells.push((col, cell_string(value)));
+        }
+        let key = cells.iter().find(|(c, _)| *c == key_col).map(|(_, v)| v.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| format!("Record {} has no value for key column {}", n, key_column))?;
+        if !seen.insert(key.clone()) {
+            return Err(format!("Duplicate key in input: {}", key));
+        }
+        match index.get(&key) {
+            Some(&row_idx) => {
+                let existing = &table.rows[row_idx];
+                let mut changed: Vec<(usize, String)> = cells.into_iter().filter(|(c, v)| !cells_equal(&existing[*c], v)).collect();
+                if changed.is_empty() {
+                    plan.unchanged += 1;
+                    continue;
+                }
+                plan.updated += 1;
+                changed.sort_by_key(|(c, _)| *c);
+                for (c, v) in changed {
+                    match plan.updates.last_mut() {
+                        Some((r, first, run)) if *r == row_idx && *first + run.len() == c => run.push(v),
+                        _ => plan.updates.push((row_idx, c, vec![v])),
+                    }
+                }
+            }
+            None => {
+                let mut row = vec![String::new(); table.headers.len()];
+                for (c, v) in cells {
+                    row[c] = v;
+                }
+                plan.appends.push(row);
+            }
+        }
+    }
+    Ok(plan)
+}
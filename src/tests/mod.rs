@@ -59,7 +59,7 @@ mod e2e {
     async fn test_create_spreadsheet_handler() {
         let client = Arc::new(GoogleApiClient::new(Arc::new(crate::google_api_client::AuthManager)).await);
         let handler = CreateSpreadsheetHandler { client };
-        let req = CreateSpreadsheetRequest { title: "TestSheet".to_string() };
+        let req = CreateSpreadsheetRequest { title: "TestSheet".to_string(), dry_run: false };
         let result = handler.handle(req).await;
         assert!(result.is_ok());
         let resp = result.unwrap();
@@ -88,6 +88,9 @@ mod e2e {
             spreadsheet_id: "dummy_id".to_string(),
             range: "Sheet1!A1:B2".to_string(),
             values: vec![vec!["A".to_string(), "B".to_string()]],
+            override_protection: true,
+            expected: None,
+            dry_run: false,
         };
         let result = handler.handle(req).await;
         assert!(result.is_ok());
@@ -119,6 +122,9 @@ mod e2e {
                 ("Sheet1!A1:B2".to_string(), vec![vec!["1".to_string(), "2".to_string()]]),
                 ("Sheet2!A1:A2".to_string(), vec![vec!["3".to_string()]])
             ],
+            override_protection: true,
+            expected: Default::default(),
+            dry_run: false,
         };
         let result = handler.handle(req).await;
         assert!(result.is_ok());
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use crate::models::McpHandler;
+
+/// Groups of tools that policy lists can name instead of listing every tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolFamily {
+    Sheets,
+    Drive,
+    Docs,
+    Calendar,
+    Permissions,
+}
+
+impl ToolFamily {
+    pub fn name(self) -> &'static str {
+        match self {
+            ToolFamily::Sheets => "sheets",
+            ToolFamily::Drive => "drive",
+            ToolFamily::Docs => "docs",
+            ToolFamily::Calendar => "calendar",
+            ToolFamily::Permissions => "permissions",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        [ToolFamily::Sheets, ToolFamily::Drive, ToolFamily::Docs, ToolFamily::Calendar, ToolFamily::Permissions]
+            .into_iter()
+            .find(|f| f.name() == s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct ToolInfo {
+    pub name: &'static str,
+    pub family: ToolFamily,
+    pub writes: bool, // hidden in read-only mode
+}
+
+const fn tool(name: &'static str, family: ToolFamily, writes: bool) -> ToolInfo {
+    ToolInfo { name, family, writes }
+}
+
+/// Every tool the servers expose. The HTTP, stdio and SEE servers only offer the ones the
+/// configured `ToolPolicy` permits.
+pub const TOOLS: &[ToolInfo] = &[
+    tool("list_spreadsheets", ToolFamily::Sheets, false),
+    tool("create_spreadsheet", ToolFamily::Sheets, true),
+    tool("get_sheet_data", ToolFamily::Sheets, false),
+    tool("set_sheet_data", ToolFamily::Sheets, true),
+    tool("batch_get_sheet_data", ToolFamily::Sheets, false),
+    tool("batch_set_sheet_data", ToolFamily::Sheets, true),
+    tool("list_drive_files", ToolFamily::Drive, false),
+    tool("get_drive_file_metadata", ToolFamily::Drive, false),
+    tool("list_permissions", ToolFamily::Permissions, false),
+    tool("add_permission", ToolFamily::Permissions, true),
+    tool("remove_permission", ToolFamily::Permissions, true),
+    tool("get_document", ToolFamily::Docs, false),
+    tool("create_document", ToolFamily::Docs, true),
+    tool("append_text", ToolFamily::Docs, true),
+    tool("list_docs", ToolFamily::Docs, false),
+    tool("get_calendar_event", ToolFamily::Calendar, false),
+    tool("create_calendar_event", ToolFamily::Calendar, true),
+    tool("list_calendar_events", ToolFamily::Calendar, false),
+    tool("list_conditional_formats", ToolFamily::Sheets, false),
+    tool("add_conditional_format", ToolFamily::Sheets, true),
+    tool("update_conditional_format", ToolFamily::Sheets, true),
+    tool("delete_conditional_format", ToolFamily::Sheets, true),
+    tool("set_data_validation", ToolFamily::Sheets, true),
+    tool("clear_data_validation", ToolFamily::Sheets, true),
+    tool("list_named_ranges", ToolFamily::Sheets, false),
+    tool("create_named_range", ToolFamily::Sheets, true),
+    tool("update_named_range", ToolFamily::Sheets, true),
+    tool("delete_named_range", ToolFamily::Sheets, true),
+    tool("list_protected_ranges", ToolFamily::Sheets, false),
+    tool("add_protected_range", ToolFamily::Sheets, true),
+    tool("update_protected_range", ToolFamily::Sheets, true),
+    tool("remove_protected_range", ToolFamily::Sheets, true),
+    tool("create_chart", ToolFamily::Sheets, true),
+    tool("update_chart", ToolFamily::Sheets, true),
+    tool("delete_chart", ToolFamily::Sheets, true),
+    tool("sheets_batch_update", ToolFamily::Sheets, true),
+    tool("read_table", ToolFamily::Sheets, false),
+    tool("upsert_rows", ToolFamily::Sheets, true),
+    tool("query_sheet", ToolFamily::Sheets, false),
+    tool("find_replace", ToolFamily::Sheets, true),
+    tool("search_cells", ToolFamily::Sheets, false),
+    tool("sort_range", ToolFamily::Sheets, true),
+    tool("set_basic_filter", ToolFamily::Sheets, true),
+    tool("clear_basic_filter", ToolFamily::Sheets, true),
+    tool("create_filter_view", ToolFamily::Sheets, true),
+    tool("delete_filter_view", ToolFamily::Sheets, true),
+    tool("get_cells", ToolFamily::Sheets, false),
+    tool("set_note", ToolFamily::Sheets, true),
+    tool("clear_notes", ToolFamily::Sheets, true),
+    tool("set_hyperlink", ToolFamily::Sheets, true),
+    tool("merge_cells", ToolFamily::Sheets, true),
+    tool("unmerge_cells", ToolFamily::Sheets, true),
+    tool("set_frozen", ToolFamily::Sheets, true),
+    tool("import_csv", ToolFamily::Sheets, true),
+    tool("export_range", ToolFamily::Sheets, false),
+    tool("upload_spreadsheet", ToolFamily::Drive, true),
+    tool("snapshot_range", ToolFamily::Sheets, false),
+    tool("diff_ranges", ToolFamily::Sheets, false),
+    tool("clear_range", ToolFamily::Sheets, true),
+    tool("list_operations", ToolFamily::Sheets, false),
+    tool("undo_operation", ToolFamily::Sheets, true),
+];
+
+pub fn lookup(name: &str) -> Option<&'static ToolInfo> {
+    TOOLS.iter().find(|t| t.name == name)
+}
+
+/// Checks that every entry is a tool or family name, so a typo in a deny list cannot
+/// silently leave a tool enabled.
+pub fn validate_names(entries: &[String]) -> Result<(), String> {
+    match entries.iter().find(|e| lookup(e).is_none() && ToolFamily::parse(e).is_none()) {
+        Some(unknown) => Err(format!("Unknown tool or tool family: {}", unknown)),
+        None => Ok(()),
+    }
+}
+
+/// Which tools a deployment offers. A tool is permitted when it is not a write tool in
+/// read-only mode, matches `allow` (if non-empty) and does not match `deny`; entries name
+/// a tool or a family.
+#[derive(Debug, Clone, Default)]
+pub struct ToolPolicy {
+    pub read_only: bool,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl ToolPolicy {
+    pub fn from_config(config: &crate::config::ToolsConfig) -> Self {
+        ToolPolicy { read_only: config.read_only, allow: config.allow.clone(), deny: config.deny.clone() }
+    }
+
+    pub fn permits(&self, tool: &ToolInfo) -> bool {
+        let matches = |entry: &String| entry == tool.name || entry == tool.family.name();
+        !(self.read_only && tool.writes)
+            && (self.allow.is_empty() || self.allow.iter().any(matches))
+            && !self.deny.iter().any(matches)
+    }
+
+    /// Ok if `name` is a known tool that this policy permits, otherwise the reason it is not.
+    pub fn check(&self, name: &str) -> Result<(), String> {
+        let tool = lookup(name).ok_or_else(|| format!("Unknown tool: {}", name))?;
+        if self.permits(tool) {
+            Ok(())
+        } else if self.read_only && tool.writes {
+            Err(format!("Tool {} is disabled: the server is read-only", name))
+        } else {
+            Err(format!("Tool {} is disabled by the tools allow/deny lists", name))
+        }
+    }
+
+    pub fn allowed_tools(&self) -> Vec<ToolInfo> {
+        TOOLS.iter().filter(|t| self.permits(t)).copied().collect()
+    }
+
+    /// Wraps `handler` so that it runs only while this policy permits `tool`.
+    pub fn guard<H>(&self, tool: &'static str, handler: H) -> Guarded<'_, H> {
+        Guarded { policy: self, tool, handler }
+    }
+}
+
+/// Why a `Guarded` handler refused to run; see `ToolPolicy::check`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolDenied(pub String);
+
+impl From<ToolDenied> for crate::models::SpreadsheetError {
+    fn from(denied: ToolDenied) -> Self {
+        crate::models::SpreadsheetError::Forbidden(denied.0)
+    }
+}
+
+impl From<ToolDenied> for crate::models::DocsError {
+    fn from(denied: ToolDenied) -> Self {
+        crate::models::DocsError::Forbidden(denied.0)
+    }
+}
+
+impl From<ToolDenied> for crate::models::CalendarError {
+    fn from(denied: ToolDenied) -> Self {
+        crate::models::CalendarError::Forbidden(denied.0)
+    }
+}
+
+/// A handler that checks the policy on every call before dispatching, so a server entry
+/// point cannot run a disabled tool even if its own routing forgets to filter it.
+pub struct Guarded<'a, H> {
+    pub policy: &'a ToolPolicy,
+    pub tool: &'static str,
+    pub handler: H,
+}
+
+#[async_trait]
+impl<H> McpHandler for Guarded<'_, H>
+where
+    H: McpHandler + Send + Sync,
+    H::Request: Send + 'static,
+    H::Error: From<ToolDenied>,
+{
+    type Request = H::Request;
+    type Response = H::Response;
+    type Error = H::Error;
+    async fn handle(&self, req: Self::Request) -> Result<Self::Response, Self::Error> {
+        self.policy.check(self.tool).map_err(|reason| H::Error::from(ToolDenied(reason)))?;
+        self.handler.handle(req).await
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Represents a set of allowed root directories for local file access.
+#[derive(Debug, Clone)]
+pub struct AllowedPaths {
+    allowed_roots: HashSet<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum AllowedPathsError {
+    NotADirectory(PathBuf),
+    DoesNotExist(PathBuf),
+    NotAllowed(PathBuf),
+    SymlinkDenied(PathBuf),
+    NotAFile(PathBuf),
+}
+
+impl fmt::Display for AllowedPathsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllowedPathsError::NotADirectory(p) => write!(f, "Not a directory: {}", p.display()),
+            AllowedPathsError::DoesNotExist(p) => write!(f, "Does not exist: {}", p.display()),
+            AllowedPathsError::NotAllowed(p) => write!(f, "Path not allowed: {}", p.display()),
+            AllowedPathsError::SymlinkDenied(p) => write!(f, "Symlink denied: {}", p.display()),
+            AllowedPathsError::NotAFile(p) => write!(f, "Not a regular file: {}", p.display()),
+        }
+    }
+}
+
+impl std::error::Error for AllowedPathsError {}
+
+impl AllowedPaths {
+    /// Create a new AllowedPaths from a list of directory roots.
+    pub fn new<P: AsRef<Path>>(roots: &[P]) -> Result<Self, AllowedPathsError> {
+        let mut allowed_roots = HashSet::new();
+        for root in roots {
+            let pb = root.as_ref().to_path_buf();
+            if !pb.exists() {
+                return Err(AllowedPathsError::DoesNotExist(pb));
+            }
+            if !pb.is_dir() {
+                return Err(AllowedPathsError::NotADirectory(pb));
+            }
+            let canonical = pb.canonicalize().map_err(|_| AllowedPathsError::DoesNotExist(pb.clone()))?;
+            allowed_roots.insert(canonical);
+        }
+        Ok(Self { allowed_roots })
+    }
+
+    /// Returns a reference to the allowed root directories.
+    pub fn get_allowed_directories(&self) -> &HashSet<PathBuf> {
+        &self.allowed_roots
+    }
+
+    /// Validate a file path for reading. Returns the canonicalized path if allowed.
+    ///
+    /// Canonicalization resolves `..` and symlinked directories before the root check, so
+    /// neither can escape an allowed root; a symlink as the final component is refused.
+    pub fn validate_file<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf, AllowedPathsError> {
+        let input = path.as_ref();
+        let metadata = input.symlink_metadata().map_err(|_| AllowedPathsError::DoesNotExist(input.to_path_buf()))?;
+        if metadata.file_type().is_symlink() {
+            tracing::warn!("Symlink denied: {}", input.display());
+            return Err(AllowedPathsError::SymlinkDenied(input.to_path_buf()));
+        }
+        if !metadata.is_file() {
+            return Err(AllowedPathsError::NotAFile(input.to_path_buf()));
+        }
+        let canonical = input.canonicalize().map_err(|_| AllowedPathsError::DoesNotExist(input.to_path_buf()))?;
+        let allowed = self.allowed_roots.iter().any(|root| canonical.starts_with(root));
+        if !allowed {
+            tracing::warn!("Denied access to path: {} (not under allowed roots)", canonical.display());
+            return Err(AllowedPathsError::NotAllowed(canonical));
+        }
+        Ok(canonical)
+    }
+}
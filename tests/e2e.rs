@@ -23,7 +23,7 @@ async fn test_list_spreadsheets_handler() {
 async fn test_create_spreadsheet_handler() {
     let client = Arc::new(GoogleApiClient::new(Arc::new(gsheets_mcp::google_api_client::AuthManager)).await);
     let handler = CreateSpreadsheetHandler { client };
-    let req = CreateSpreadsheetRequest { title: "TestSheet".to_string() };
+    let req = CreateSpreadsheetRequest { title: "TestSheet".to_string(), dry_run: false };
     let result = handler.handle(req).await;
     assert!(result.is_ok());
     let resp = result.unwrap();
@@ -52,6 +52,9 @@ async fn test_set_sheet_data_handler_e2e() {
         spreadsheet_id: "dummy_id".to_string(),
         range: "Sheet1!A1:B2".to_string(),
         values: vec![vec!["A".to_string(), "B".to_string()]],
+        override_protection: true,
+        expected: None,
+        dry_run: false,
     };
     let result = handler.handle(req).await;
     assert!(result.is_ok());
@@ -83,6 +86,9 @@ async fn test_batch_set_sheet_data_handler_e2e() {
             ("Sheet1!A1:B2".to_string(), vec![vec!["1".to_string(), "2".to_string()]]),
             ("Sheet2!A1:A2".to_string(), vec![vec!["3".to_string()]])
         ],
+        override_protection: true,
+        expected: Default::default(),
+        dry_run: false,
     };
     let result = handler.handle(req).await;
     assert!(result.is_ok());
@@ -112,3 +118,48 @@ async fn test_get_drive_file_metadata_handler_e2e() {
     assert_eq!(resp.mime_type, "application/vnd.google-apps.spreadsheet");
     assert_eq!(resp.size, Some(1024));
 }
+
+#[tokio::test]
+async fn test_set_sheet_data_expected_e2e() {
+    use gsheets_mcp::models::{ExpectedContent, SpreadsheetError};
+    let client = Arc::new(GoogleApiClient::new(Arc::new(gsheets_mcp::google_api_client::AuthManager)).await);
+    let spreadsheet_id = std::env::var("E2E_SPREADSHEET_ID").unwrap_or_else(|_| "dummy_id".to_string());
+    let row = |cells: &[&str]| vec![cells.iter().map(|c| c.to_string()).collect::<Vec<_>>()];
+    let set = |values: Vec<Vec<String>>, expected: Option<ExpectedContent>| SetSheetDataRequest {
+        spreadsheet_id: spreadsheet_id.clone(),
+        range: "Sheet1!A1:B1".to_string(),
+        values,
+        override_protection: true,
+        expected,
+        dry_run: false,
+    };
+    let handler = SetSheetDataHandler { client: client.clone() };
+    handler.handle(set(row(&["a", "b"]), None)).await.unwrap();
+
+    let stale = handler.handle(set(row(&["c", "d"]), Some(ExpectedContent::Values(row(&["x", "y"]))))).await;
+    assert!(matches!(stale, Err(SpreadsheetError::Conflict(_))));
+
+    let resp = handler.handle(set(row(&["c", "d"]), Some(ExpectedContent::Values(row(&["a", "b"]))))).await.unwrap();
+    assert_eq!(resp.updated_cells, 2);
+    let read = GetSheetDataHandler { client }
+        .handle(GetSheetDataRequest { spreadsheet_id: spreadsheet_id.clone(), range: "Sheet1!A1:B1".to_string() })
+        .await
+        .unwrap();
+    assert_eq!(read.values, row(&["c", "d"]));
+}
+
+#[tokio::test]
+async fn test_sheets_access_denied_is_forbidden_e2e() {
+    use gsheets_mcp::config::AccessConfig;
+    use gsheets_mcp::models::SpreadsheetError;
+    let policy = gsheets_mcp::access::AccessPolicy::from_config(&AccessConfig {
+        spreadsheet_ids: vec!["allowed_id".to_string()],
+        ..Default::default()
+    });
+    let client = Arc::new(GoogleApiClient::new(Arc::new(gsheets_mcp::google_api_client::AuthManager)).await
+        .with_access_policy(policy));
+    let result = GetSheetDataHandler { client }
+        .handle(GetSheetDataRequest { spreadsheet_id: "other_id".to_string(), range: "Sheet1!A1".to_string() })
+        .await;
+    assert!(matches!(result, Err(SpreadsheetError::Forbidden(_))));
+}
@@ -3,6 +3,10 @@ use gsheets_mcp::auth_manager::{AuthMethod, AuthManager};
 use gsheets_mcp::config::AppConfig;
 use std::sync::Arc;
 
+fn grid(rows: Vec<Vec<&str>>) -> Vec<Vec<String>> {
+    rows.into_iter().map(|r| r.into_iter().map(String::from).collect()).collect()
+}
+
 #[test]
 fn test_auth_method_from_str() {
     assert_eq!(AuthMethod::from_str("service_account"), Some(AuthMethod::ServiceAccount));
@@ -29,3 +33,691 @@ async fn test_auth_manager_new_service_account_missing_key() {
 // Removed handler logic tests for get/set sheet data (moved to src/tests/mod.rs)
 
 // Add more tests for GoogleApiClient as needed, using mocks or stubs.
+
+#[test]
+fn test_a1_range_parse_and_display() {
+    use gsheets_mcp::a1::A1Range;
+    let r = A1Range::parse("Sheet1!A1:C10").unwrap();
+    assert_eq!(r.sheet.as_deref(), Some("Sheet1"));
+    assert_eq!((r.start_col, r.start_row, r.end_col, r.end_row), (Some(0), Some(0), Some(3), Some(10)));
+    assert_eq!(r.to_string(), "Sheet1!A1:C10");
+
+    let r = A1Range::parse("'My Sheet'!B:B").unwrap();
+    assert_eq!(r.sheet.as_deref(), Some("My Sheet"));
+    assert_eq!((r.start_row, r.end_row), (None, None));
+    assert_eq!(r.to_string(), "'My Sheet'!B:B");
+
+    assert_eq!(A1Range::parse("AA5").unwrap().to_string(), "AA5");
+    assert_eq!(A1Range::parse("Summary").unwrap().sheet.as_deref(), Some("Summary"));
+    assert!(A1Range::parse("Sheet1!A0").is_err());
+
+    let r = A1Range::parse("Sheet1!B5:A1").unwrap();
+    assert_eq!(r.to_string(), "Sheet1!A1:B5");
+    assert_eq!(r.dimensions(), Some((5, 2)));
+    assert_eq!(A1Range::parse("C2:C1").unwrap().to_string(), "C1:C2");
+
+    let empty = google_sheets4::api::GridRange { start_row_index: Some(3), end_row_index: Some(0), end_column_index: Some(0), ..Default::default() };
+    let r = A1Range::from_grid_range(&empty, None);
+    assert_eq!(r.dimensions(), None);
+    let _ = r.to_string();
+}
+
+#[test]
+fn test_hex_color_round_trip() {
+    use gsheets_mcp::models::{color_to_hex, parse_hex_color};
+    let color = parse_hex_color("#FF8000").unwrap();
+    assert_eq!(color_to_hex(&color), "#FF8000");
+    assert!(parse_hex_color("red").is_err());
+}
+
+#[test]
+fn test_validation_rule_conditions() {
+    use gsheets_mcp::models::ValidationRule;
+    let list = ValidationRule::List { values: vec!["open".to_string(), "done".to_string()], show_dropdown: None };
+    let condition = list.to_condition().unwrap();
+    assert_eq!(condition.type_.as_deref(), Some("ONE_OF_LIST"));
+    assert_eq!(condition.values.unwrap().len(), 2);
+
+    let checkbox = ValidationRule::Checkbox { checked_value: None, unchecked_value: None };
+    assert_eq!(checkbox.to_condition().unwrap().type_.as_deref(), Some("BOOLEAN"));
+
+    let between = ValidationRule::Number { condition: "between".to_string(), values: vec!["1".to_string()] };
+    assert!(between.to_condition().is_err());
+}
+
+#[test]
+fn test_validate_named_range_name() {
+    use gsheets_mcp::models::validate_named_range_name;
+    assert!(validate_named_range_name("Totals").is_ok());
+    assert!(validate_named_range_name("q3_revenue").is_ok());
+    assert!(validate_named_range_name("1st").is_err());
+    assert!(validate_named_range_name("A1").is_err());
+    assert!(validate_named_range_name("has space").is_err());
+    assert!(validate_named_range_name("TRUE").is_err());
+}
+
+#[test]
+fn test_grid_range_overlap_and_written_extent() {
+    use gsheets_mcp::a1::{grid_range_contains, grid_ranges_overlap, written_extent, A1Range};
+    let header = A1Range::parse("Sheet1!1:1").unwrap().to_grid_range(0);
+    let write = A1Range::parse("Sheet1!B1").unwrap().to_grid_range(0);
+    let values = vec![vec!["x".to_string()], vec!["y".to_string()]];
+    let extent = written_extent(&write, &values);
+    assert_eq!((extent.start_row_index, extent.end_row_index), (Some(0), Some(2)));
+    assert!(grid_ranges_overlap(&extent, &header));
+
+    let body = A1Range::parse("Sheet1!A2:C10").unwrap().to_grid_range(0);
+    assert!(!grid_ranges_overlap(&header, &body));
+    assert!(!grid_ranges_overlap(&body, &A1Range::parse("Other!A2:C10").unwrap().to_grid_range(7)));
+    assert!(grid_range_contains(&body, &A1Range::parse("Sheet1!B3:C4").unwrap().to_grid_range(0)));
+}
+
+#[test]
+fn test_check_protection_refuses_protected_writes() {
+    use gsheets_mcp::a1::{written_extent, A1Range};
+    use gsheets_mcp::models::{check_protection, SpreadsheetError};
+    use google_sheets4::api::{ProtectedRange, Sheet, SheetProperties, Spreadsheet};
+    let header = A1Range::parse("Sheet1!A1:C1").unwrap().to_grid_range(0);
+    let spreadsheet = Spreadsheet {
+        sheets: Some(vec![Sheet {
+            properties: Some(SheetProperties { sheet_id: Some(0), title: Some("Sheet1".to_string()), ..Default::default() }),
+            protected_ranges: Some(vec![ProtectedRange {
+                protected_range_id: Some(7),
+                range: Some(header),
+                unprotected_ranges: Some(vec![A1Range::parse("Sheet1!C1").unwrap().to_grid_range(0)]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let cells = |range: &str, rows: usize, cols: usize| {
+        let grid = A1Range::parse(range).unwrap().to_grid_range(0);
+        (range.to_string(), written_extent(&grid, &vec![vec![String::new(); cols]; rows]))
+    };
+    let refused = check_protection(&spreadsheet, &[cells("Sheet1!A3", 1, 1), cells("Sheet1!B1", 2, 1)]);
+    match refused {
+        Err(SpreadsheetError::Protected(message)) => assert!(message.contains("Sheet1!B1") && message.contains("7")),
+        other => panic!("expected a protection error, got {:?}", other),
+    }
+    assert!(check_protection(&spreadsheet, &[cells("Sheet1!A2", 5, 3)]).is_ok());
+    assert!(check_protection(&spreadsheet, &[cells("Sheet1!C1", 1, 1)]).is_ok());
+    assert!(check_protection(&spreadsheet, &[cells("Sheet1!B1", 1, 2)]).is_err());
+}
+
+#[test]
+fn test_chart_spec_from_source_columns() {
+    use gsheets_mcp::a1::A1Range;
+    use gsheets_mcp::models::{split_grid_columns, ChartOptions, ChartType};
+    let source = A1Range::parse("Sheet1!A1:C10").unwrap();
+    let mut columns = split_grid_columns(&source.to_grid_range(0)).unwrap().into_iter();
+    let domain = columns.next().unwrap();
+    let series: Vec<_> = columns.map(|c| (c, None)).collect();
+    assert_eq!(series.len(), 2);
+
+    let options = ChartOptions {
+        chart_type: ChartType::Column,
+        source: source.clone(),
+        title: Some("Revenue".to_string()),
+        header_rows: None,
+        x_axis_title: None,
+        y_axis_title: None,
+        legend_position: None,
+        series: None,
+    };
+    let spec = options.to_spec(domain.clone(), series.clone()).unwrap();
+    let basic = spec.basic_chart.unwrap();
+    assert_eq!(basic.chart_type.as_deref(), Some("COLUMN"));
+    assert_eq!(basic.series.unwrap().len(), 2);
+
+    let pie = ChartOptions { chart_type: ChartType::Pie, ..options };
+    assert!(pie.to_spec(domain, series).is_err());
+}
+
+#[test]
+fn test_validate_raw_requests() {
+    use gsheets_mcp::models::{validate_raw_requests, SpreadsheetStructure};
+    let mut structure = SpreadsheetStructure::default();
+    structure.sheet_ids.insert(0);
+    let parse = |json: &str| -> Vec<google_sheets4::api::Request> { serde_json::from_str(json).unwrap() };
+
+    let ok = parse(r#"[{"updateSheetProperties": {"properties": {"sheetId": 0, "title": "Data"}, "fields": "title"}}]"#);
+    assert!(validate_raw_requests(&ok, &structure).is_ok());
+
+    let unknown = parse(r#"[{"deleteSheet": {"sheetId": 42}}]"#);
+    assert!(validate_raw_requests(&unknown, &structure).is_err());
+
+    let added_then_used = parse(r#"[
+        {"addSheet": {"properties": {"sheetId": 42, "title": "New"}}},
+        {"deleteSheet": {"sheetId": 42}}
+    ]"#);
+    assert!(validate_raw_requests(&added_then_used, &structure).is_ok());
+
+    let empty = parse(r#"[{}]"#);
+    assert!(validate_raw_requests(&empty, &structure).is_err());
+}
+
+#[test]
+fn test_raw_request_cells() {
+    use gsheets_mcp::models::raw_request_cells;
+    let requests: Vec<google_sheets4::api::Request> = serde_json::from_str(r#"[
+        {"repeatCell": {"range": {"sheetId": 0, "startRowIndex": 0, "endRowIndex": 1}, "cell": {}, "fields": "userEnteredFormat"}},
+        {"deleteDimension": {"range": {"sheetId": 0, "dimension": "COLUMNS", "startIndex": 2, "endIndex": 3}}},
+        {"updateCells": {"start": {"sheetId": 0, "rowIndex": 4, "columnIndex": 1}, "fields": "*"}},
+        {"addNamedRange": {"namedRange": {"name": "Totals", "range": {"sheetId": 0}}}}
+    ]"#).unwrap();
+    let cells = raw_request_cells(&requests);
+    assert_eq!(cells.len(), 3);
+    assert_eq!(cells[0].0, "request 0 (repeatCell)");
+    assert_eq!((cells[0].1.start_row_index, cells[0].1.end_row_index), (Some(0), Some(1)));
+    assert_eq!((cells[1].1.start_column_index, cells[1].1.end_column_index, cells[1].1.start_row_index), (Some(2), Some(3), None));
+    assert_eq!((cells[2].1.start_row_index, cells[2].1.start_column_index, cells[2].1.end_row_index), (Some(4), Some(1), None));
+}
+
+#[test]
+fn test_table_header_detection_and_types() {
+    use gsheets_mcp::table::{ColumnType, Table};
+    let grid: Vec<Vec<String>> = vec![
+        vec!["Q3 report"],
+        vec![],
+        vec!["id", "name", "amount", "paid", "due"],
+        vec!["1", "Acme", "1,200.50", "TRUE", "2024-01-05"],
+        vec!["2", "Globex", "300", "false", "1/31/2024"],
+        vec![],
+        vec![""],
+    ].into_iter().map(|r| r.into_iter().map(String::from).collect()).collect();
+    let table = Table::from_values(&grid, None).unwrap();
+    assert_eq!(table.header_row, 2);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.column_types, vec![
+        ColumnType::Number, ColumnType::Text, ColumnType::Number, ColumnType::Boolean, ColumnType::Date,
+    ]);
+    let record = table.record(&table.rows[1]);
+    assert_eq!(record["amount"], serde_json::json!(300.0));
+    assert_eq!(record["paid"], serde_json::json!(false));
+    assert_eq!(record["due"], serde_json::json!("2024-01-31"));
+}
+
+#[test]
+fn test_plan_upsert() {
+    use gsheets_mcp::table::{plan_upsert, Table};
+    let grid: Vec<Vec<String>> = vec![
+        vec!["id", "name", "total", "amount", "status"],
+        vec!["1", "Acme", "2,000", "1,000", "open"],
+        vec!["2", "Globex", "100", "50", "open"],
+    ].into_iter().map(|r| r.into_iter().map(String::from).collect()).collect();
+    let table = Table::from_values(&grid, Some(0)).unwrap();
+    let records: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_value(serde_json::json!([
+        {"id": 1, "amount": 1000},
+        {"id": 2, "name": "Globex Corp", "amount": 60, "status": "closed"},
+        {"id": 3, "name": "Initech", "amount": 7}
+    ])).unwrap();
+    let plan = plan_upsert(&table, "id", &records).unwrap();
+    assert_eq!((plan.updated, plan.unchanged), (1, 1));
+    // The unchanged total between name and amount (a formula, say) is not rewritten.
+    assert_eq!(plan.updates, vec![
+        (1, 1, vec!["Globex Corp".to_string()]),
+        (1, 3, vec!["60".to_string(), "closed".to_string()]),
+    ]);
+    assert_eq!(plan.appends, vec![vec!["3".to_string(), "Initech".to_string(), String::new(), "7".to_string(), String::new()]]);
+
+    let duplicate: Vec<serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_value(serde_json::json!([{"id": 9}, {"id": 9}])).unwrap();
+    assert!(plan_upsert(&table, "id", &duplicate).is_err());
+}
+
+#[test]
+fn test_query_sheet_language() {
+    use gsheets_mcp::query::parse;
+    use gsheets_mcp::table::Table;
+    let grid: Vec<Vec<String>> = vec![
+        vec!["name", "status", "amount", "Due Date"],
+        vec!["a", "open", "150", "2024-03-01"],
+        vec!["b", "closed", "900", "2024-01-15"],
+        vec!["c", "Open", "99", "2024-02-01"],
+        vec!["d", "open", "1,200", "2024-04-30"],
+    ].into_iter().map(|r| r.into_iter().map(String::from).collect()).collect();
+    let table = Table::from_values(&grid, Some(0)).unwrap();
+
+    let q = parse("SELECT name, amount WHERE status = 'open' AND amount > 100 ORDER BY \"Due Date\" DESC LIMIT 20").unwrap();
+    let result = q.execute(&table).unwrap();
+    assert_eq!(result.columns, vec!["name", "amount"]);
+    assert_eq!(result.rows.iter().map(|r| r[0].as_str()).collect::<Vec<_>>(), vec!["d", "a"]);
+
+    let q = parse("SELECT * WHERE NOT (status = 'closed') OR name LIKE 'b%' LIMIT 2 OFFSET 1").unwrap();
+    let result = q.execute(&table).unwrap();
+    assert_eq!(result.matched, 4);
+    assert_eq!(result.rows.len(), 2);
+
+    assert!(parse("SELECT name WHERE").is_err());
+    assert!(parse("SELECT missing").unwrap().execute(&table).is_err());
+}
+
+#[test]
+fn test_cell_matcher_options() {
+    use gsheets_mcp::search::CellMatcher;
+    let literal = CellMatcher::new("acme", false, false, false).unwrap();
+    assert_eq!(literal.count("ACME Corp / acme ltd"), 2);
+    assert_eq!(literal.replace("Acme (US)", "Globex"), "Globex (US)");
+
+    let case = CellMatcher::new("acme", false, true, false).unwrap();
+    assert!(!case.is_match("ACME"));
+
+    let entire = CellMatcher::new("a.c", false, false, true).unwrap();
+    assert!(entire.is_match("A.C"));
+    assert!(!entire.is_match("abc"));
+    assert!(!entire.is_match("a.c2"));
+
+    let regex = CellMatcher::new(r"(\d+)-(\d+)", true, false, false).unwrap();
+    assert_eq!(regex.replace("10-20", "$2-$1"), "20-10");
+    assert!(CellMatcher::new("(", true, false, false).is_err());
+    assert!(CellMatcher::new("", false, false, false).is_err());
+}
+
+#[test]
+fn test_matching_cells_addresses() {
+    use gsheets_mcp::a1::A1Range;
+    use gsheets_mcp::search::{matching_cells, CellMatcher};
+    let values: Vec<Vec<String>> = vec![
+        vec!["Client", "Total"],
+        vec!["ACME Corp", "=SUM(B3:B9)"],
+        vec!["", "acme corp"],
+    ].into_iter().map(|r| r.into_iter().map(String::from).collect()).collect();
+    let matcher = CellMatcher::new("acme corp", false, false, false).unwrap();
+    let hits = matching_cells(&matcher, &values, false);
+    assert_eq!(hits, vec![(1, 0), (2, 1)]);
+
+    let origin = A1Range::parse("'Q1 Sales'!C10:D12").unwrap();
+    assert_eq!(origin.cell_at(2, 1).to_string(), "'Q1 Sales'!D12");
+
+    let sum = CellMatcher::new("SUM", false, false, false).unwrap();
+    assert!(matching_cells(&sum, &values, false).is_empty());
+    assert_eq!(matching_cells(&sum, &values, true), vec![(1, 1)]);
+}
+
+#[test]
+fn test_resolve_sort_column() {
+    use gsheets_mcp::models::resolve_column;
+    let headers: Vec<String> = vec!["Name".to_string(), "ID".to_string(), "Amount".to_string()];
+    assert_eq!(resolve_column("Amount", &headers, 2), Ok(4));
+    assert_eq!(resolve_column("amount", &headers, 2), Ok(4));
+    // Header names take precedence over column letters.
+    assert_eq!(resolve_column("ID", &headers, 0), Ok(1));
+    assert_eq!(resolve_column("D", &headers, 0), Ok(3));
+    assert!(resolve_column("Total Due", &headers, 0).is_err());
+}
+
+#[test]
+fn test_cell_info_formula_and_value() {
+    use google_sheets4::api::{CellData, ExtendedValue};
+    use gsheets_mcp::models::cell_info;
+    let data = CellData {
+        user_entered_value: Some(ExtendedValue { formula_value: Some("=SUM(B2:B10)".to_string()), ..Default::default() }),
+        effective_value: Some(ExtendedValue { number_value: Some(1250.5), ..Default::default() }),
+        formatted_value: Some("$1,250.50".to_string()),
+        note: Some("checked".to_string()),
+        ..Default::default()
+    };
+    let info = cell_info("Sheet1!B11".to_string(), &data).unwrap();
+    assert_eq!(info.formula.as_deref(), Some("=SUM(B2:B10)"));
+    assert_eq!(info.value, serde_json::json!(1250.5));
+    assert_eq!(info.formatted.as_deref(), Some("$1,250.50"));
+    assert!(cell_info("Sheet1!C1".to_string(), &CellData::default()).is_none());
+}
+
+#[test]
+fn test_hyperlink_cell_data() {
+    use gsheets_mcp::models::{hyperlink_cell, hyperlink_formula};
+    assert_eq!(
+        hyperlink_formula("https://example.com/?q=\"x\"", "See \"x\""),
+        "=HYPERLINK(\"https://example.com/?q=\"\"x\"\"\", \"See \"\"x\"\"\")"
+    );
+    let rich = hyperlink_cell("https://example.com", "Example", false);
+    assert_eq!(rich.user_entered_value.and_then(|v| v.string_value).as_deref(), Some("Example"));
+    let link = rich.text_format_runs.unwrap()[0].format.as_ref().and_then(|f| f.link.as_ref()).and_then(|l| l.uri.clone());
+    assert_eq!(link.as_deref(), Some("https://example.com"));
+    let formula = hyperlink_cell("https://example.com", "Example", true);
+    assert!(formula.user_entered_value.and_then(|v| v.formula_value).unwrap().starts_with("=HYPERLINK("));
+}
+
+#[test]
+fn test_validate_merge() {
+    use gsheets_mcp::a1::A1Range;
+    use gsheets_mcp::models::{validate_merge, MergeType};
+    let title = A1Range::parse("Report!A1:F1").unwrap();
+    assert!(validate_merge(&title, MergeType::All).is_ok());
+    assert!(validate_merge(&title, MergeType::Rows).is_ok());
+    assert!(validate_merge(&title, MergeType::Columns).is_err());
+    assert!(validate_merge(&A1Range::parse("Report!B2").unwrap(), MergeType::All).is_err());
+    assert!(validate_merge(&A1Range::parse("Report!A:B").unwrap(), MergeType::All).is_err());
+    let parsed: MergeType = serde_json::from_str("\"columns\"").unwrap();
+    assert_eq!(parsed, MergeType::Columns);
+}
+
+#[test]
+fn test_parse_delimited_rfc4180() {
+    use gsheets_mcp::delimited::{parse_delimited, sniff_delimiter, split_unquoted};
+    let csv = "name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\r\nLee,\n";
+    let rows = parse_delimited(csv, ',', None).unwrap();
+    assert_eq!(rows, vec![
+        vec!["name".to_string(), "note".to_string()],
+        vec!["Smith, J".to_string(), "said \"hi\"\nthen left".to_string()],
+        vec!["Lee".to_string(), String::new()],
+    ]);
+    assert!(parse_delimited("a,\"open", ',', None).is_err());
+
+    assert_eq!(sniff_delimiter("a\tb\tc\n1\t2\t3"), '\t');
+    assert_eq!(sniff_delimiter("a;b;\"c,d\""), ';');
+
+    let one_line = parse_delimited("a,\"b;c\";1,2", ',', Some(';')).unwrap();
+    assert_eq!(one_line, vec![vec!["a".to_string(), "b;c".to_string()], vec!["1".to_string(), "2".to_string()]]);
+    assert_eq!(split_unquoted("Sheet1!A1:B1=\"x;y\",z;Sheet2!C3=1", ';'), vec!["Sheet1!A1:B1=\"x;y\",z", "Sheet2!C3=1"]);
+}
+
+#[test]
+fn test_export_text_formats() {
+    use gsheets_mcp::delimited::{parse_delimited, write_delimited};
+    use gsheets_mcp::table::Table;
+    let values: Vec<Vec<String>> = vec![
+        vec!["item", "notes"],
+        vec!["widget", "a | b, \"c\""],
+        vec!["gadget", "line1\nline2"],
+    ].into_iter().map(|r| r.into_iter().map(String::from).collect()).collect();
+
+    let csv = write_delimited(&values, ',');
+    assert!(csv.starts_with("item,notes\r\nwidget,\"a | b, \"\"c\"\"\"\r\n"));
+    assert_eq!(parse_delimited(&csv, ',', None).unwrap(), values);
+    assert_eq!(write_delimited(&values[..1], '\t'), "item\tnotes\r\n");
+
+    let markdown = Table::from_values(&values, Some(0)).unwrap().to_markdown();
+    assert_eq!(markdown, "| item | notes |\n| --- | --- |\n| widget | a \\| b, \"c\" |\n| gadget | line1<br>line2 |\n");
+}
+
+#[test]
+fn test_allowed_paths_for_uploads() {
+    use gsheets_mcp::models::spreadsheet_source_mime;
+    use gsheets_mcp::validation::AllowedPaths;
+    let root = std::env::temp_dir().join(format!("gsheets_mcp_uploads_{}", std::process::id()));
+    let inside = root.join("in");
+    std::fs::create_dir_all(&inside).unwrap();
+    let file = inside.join("Budget.XLSX");
+    std::fs::write(&file, b"data").unwrap();
+    let outside = root.join("outside.xlsx");
+    std::fs::write(&outside, b"data").unwrap();
+
+    let allowed = AllowedPaths::new(&[&inside]).unwrap();
+    assert!(allowed.validate_file(&file).is_ok());
+    assert!(allowed.validate_file(inside.join("../outside.xlsx")).is_err());
+    assert!(allowed.validate_file(&outside).is_err());
+    assert!(allowed.validate_file(&inside).is_err());
+    #[cfg(unix)]
+    {
+        let link = inside.join("link.xlsx");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+        assert!(allowed.validate_file(&link).is_err());
+    }
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(spreadsheet_source_mime("Budget.XLSX"), Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"));
+    assert_eq!(spreadsheet_source_mime("notes.txt"), None);
+}
+
+#[test]
+fn test_diff_cells_and_rows() {
+    use gsheets_mcp::diff::{diff_cells, summarize_rows, ChangeKind};
+    use gsheets_mcp::table::Table;
+    let before = grid(vec![
+        vec!["id", "name", "amount"],
+        vec!["1", "Acme", "100"],
+        vec!["2", "Globex", "250"],
+        vec!["3", "Initech", "75"],
+    ]);
+    let after = grid(vec![
+        vec!["id", "name", "amount"],
+        vec!["1", "Acme", "120"],
+        vec!["3", "Initech", "75"],
+        vec!["4", "Umbrella", ""],
+    ]);
+    let changes = diff_cells(&before, &after);
+    assert_eq!(changes[0].kind, ChangeKind::Modified);
+    assert_eq!((changes[0].row, changes[0].col), (1, 2));
+    assert_eq!(changes.last().map(|c| (c.kind, c.before.as_deref())), Some((ChangeKind::Deleted, Some("75"))));
+
+    let old = Table::from_values(&before, Some(0)).unwrap();
+    let new = Table::from_values(&after, Some(0)).unwrap();
+    let summary = summarize_rows(&old, &new, Some("id")).unwrap();
+    assert_eq!(summary.added, vec!["4"]);
+    assert_eq!(summary.removed, vec!["2"]);
+    assert_eq!(summary.modified.len(), 1);
+    assert_eq!(summary.modified[0].key, "1");
+    assert_eq!(summary.modified[0].columns, vec!["amount"]);
+    assert_eq!(summary.unchanged, 1);
+    assert!(summarize_rows(&old, &new, Some("missing")).is_err());
+}
+
+#[test]
+fn test_write_changes_only_covers_written_cells() {
+    use gsheets_mcp::diff::{write_changes, ChangeKind};
+    let current = grid(vec![vec!["a", "b", "c"], vec!["d"]]);
+    let values = grid(vec![vec!["a", "B"], vec!["", "e"]]);
+    let changes = write_changes(&current, &values);
+    let summary: Vec<_> = changes.iter().map(|c| (c.row, c.col, c.kind)).collect();
+    assert_eq!(summary, vec![(0, 1, ChangeKind::Modified), (1, 0, ChangeKind::Deleted), (1, 1, ChangeKind::Added)]);
+    assert!(write_changes(&current, &grid(vec![vec!["a"]])).is_empty());
+}
+
+#[test]
+fn test_content_hash_ignores_trailing_blanks() {
+    use gsheets_mcp::diff::content_hash;
+    let base = content_hash(&grid(vec![vec!["a", "b"], vec!["c"]]));
+    assert_eq!(base.len(), 16);
+    assert_eq!(base, content_hash(&grid(vec![vec!["a", "b", ""], vec!["c"], vec![""]])));
+    assert_ne!(base, content_hash(&grid(vec![vec!["a", "b"], vec!["d"]])));
+    assert_ne!(content_hash(&grid(vec![vec!["ab"]])), content_hash(&grid(vec![vec!["a", "b"]])));
+    assert_ne!(content_hash(&grid(vec![vec!["a", "b"]])), content_hash(&grid(vec![vec!["a"], vec!["b"]])));
+    assert_eq!(content_hash(&[]), content_hash(&grid(vec![vec![""]])));
+}
+
+#[test]
+fn test_journal_records_and_folds_undo() {
+    use gsheets_mcp::journal::{pad_grid, Journal, RangeBackup};
+    let path = std::env::temp_dir().join(format!("gsheets_mcp_journal_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let journal = Journal::new(&path);
+    assert!(journal.operations().unwrap().is_empty());
+
+    let before = pad_grid(vec![vec!["=SUM(A1:A2)".to_string()]], 2, 2);
+    assert_eq!(before, vec![vec!["=SUM(A1:A2)".to_string(), String::new()], vec![String::new(), String::new()]]);
+    let first = journal.record("set_sheet_data", "sheet-1", vec![RangeBackup { range: "Sheet1!B2:C3".to_string(), before }]).unwrap();
+    let second = journal.record("clear_range", "sheet-1", Vec::new()).unwrap();
+    assert_ne!(first, second);
+    journal.mark_undone(&first, Some(second.clone())).unwrap();
+
+    let operations = journal.operations().unwrap();
+    assert_eq!(operations.len(), 2);
+    assert_eq!(operations[0].undone_by.as_deref(), Some(second.as_str()));
+    assert_eq!(operations[0].ranges[0].range, "Sheet1!B2:C3");
+    assert_eq!(operations[1].undone_by, None);
+
+    std::fs::write(&path, "not json\n").unwrap();
+    assert!(journal.operations().unwrap_err().contains("line 1"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_tool_policy() {
+    use gsheets_mcp::tools::{validate_names, ToolPolicy, TOOLS};
+    let all = ToolPolicy::default();
+    assert_eq!(all.allowed_tools().len(), TOOLS.len());
+
+    let analyst = ToolPolicy { read_only: true, allow: vec!["sheets".to_string(), "list_drive_files".to_string()], deny: vec!["export_range".to_string()] };
+    assert!(analyst.check("get_sheet_data").is_ok());
+    assert!(analyst.check("list_drive_files").is_ok());
+    assert!(analyst.check("set_sheet_data").unwrap_err().contains("read-only"));
+    assert!(analyst.check("export_range").is_err());
+    assert!(analyst.check("get_document").is_err());
+    assert!(analyst.check("no_such_tool").is_err());
+    assert!(analyst.allowed_tools().iter().all(|t| !t.writes));
+
+    let no_sharing = ToolPolicy { deny: vec!["permissions".to_string()], ..Default::default() };
+    assert!(no_sharing.check("add_permission").is_err());
+    assert!(no_sharing.check("create_document").is_ok());
+
+    assert!(validate_names(&["drive".to_string(), "undo_operation".to_string()]).is_ok());
+    assert!(validate_names(&["sheet".to_string()]).is_err());
+}
+
+#[tokio::test]
+async fn test_guarded_handler_refuses_disabled_tools() {
+    use gsheets_mcp::models::{McpHandler, SpreadsheetError};
+    use gsheets_mcp::tools::ToolPolicy;
+    struct Counting(std::sync::atomic::AtomicUsize);
+    #[async_trait::async_trait]
+    impl McpHandler for Counting {
+        type Request = ();
+        type Response = ();
+        type Error = SpreadsheetError;
+        async fn handle(&self, _req: ()) -> Result<(), SpreadsheetError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+    }
+    let read_only = ToolPolicy { read_only: true, ..Default::default() };
+    let set = read_only.guard("set_sheet_data", Counting(Default::default()));
+    match set.handle(()).await {
+        Err(SpreadsheetError::Forbidden(reason)) => assert!(reason.contains("read-only")),
+        other => panic!("expected Forbidden, got {:?}", other),
+    }
+    assert_eq!(set.handler.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    let get = read_only.guard("get_sheet_data", Counting(Default::default()));
+    assert!(get.handle(()).await.is_ok());
+    assert_eq!(get.handler.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_http_router_refuses_disabled_tools() {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use gsheets_mcp::config::ToolsConfig;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+    let config = AppConfig {
+        tools: ToolsConfig { read_only: true, deny: vec!["export_range".to_string()], ..Default::default() },
+        ..Default::default()
+    };
+    let router = gsheets_mcp::http_server::tool_router(Arc::new(config));
+    let post = |path: &str| Request::post(path).header("content-type", "application/json").body(Body::from("{}")).unwrap();
+
+    for path in ["/sheets/set", "/sheets/export", "/operations/undo"] {
+        let resp = router.clone().oneshot(post(path)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{}", path);
+    }
+
+    let resp = router.oneshot(post("/tools/list")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let tools: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+    assert!(names.contains(&"get_sheet_data"));
+    assert!(!names.contains(&"set_sheet_data"));
+    assert!(!names.contains(&"export_range"));
+}
+
+#[test]
+fn test_stdio_command_tools() {
+    use gsheets_mcp::stdio_server::command_tool;
+    use gsheets_mcp::tools::lookup;
+    let legacy = "list create get set batch_get batch_set drive_list drive_metadata permissions_list permissions_add \
+        permissions_remove docs_get docs_create docs_append docs_list calendar_get_event calendar_create_event \
+        calendar_list_events conditional_formats_list conditional_formats_add conditional_formats_update conditional_formats_delete";
+    for command in legacy.split_whitespace() {
+        assert!(lookup(command_tool(command)).is_some(), "{}", command);
+    }
+    assert_eq!(command_tool("batch_set"), "batch_set_sheet_data");
+    assert_eq!(command_tool("import_csv"), "import_csv");
+}
+
+#[test]
+fn test_stdio_parse_batch_set_pair() {
+    use gsheets_mcp::stdio_server::parse_batch_set_pair as parse;
+    assert_eq!(parse("Sheet1!A1:B2=1,2|3,4").unwrap(), ("Sheet1!A1:B2".to_string(), grid(vec![vec!["1", "2"], vec!["3", "4"]])));
+    assert_eq!(parse("C1=\"a=b\"").unwrap(), ("C1".to_string(), grid(vec![vec!["a=b"]])));
+    // The older cell:csv form, including csv text that contains `=`.
+    assert_eq!(parse("A1:x,y").unwrap(), ("A1".to_string(), grid(vec![vec!["x", "y"]])));
+    assert_eq!(parse("Sheet1!B3:1+1=2").unwrap(), ("Sheet1!B3".to_string(), grid(vec![vec!["1+1=2"]])));
+    // `A1:x=y` reads as A1:X = y or as A1 = x=y.
+    assert!(parse("A1:x=y").unwrap_err().contains("Ambiguous"));
+    // Legacy parsing needs a single cell before the `:`.
+    assert!(parse("A:x,y").is_err());
+    assert!(parse("nonsense").is_err());
+}
+
+#[test]
+fn test_access_policy_lists() {
+    use gsheets_mcp::access::AccessPolicy;
+    use gsheets_mcp::config::AccessConfig;
+    let open = AccessPolicy::default();
+    assert!(!open.restricts_files());
+    assert!(open.file_listed("anything"));
+    assert!(open.calendar_allowed("primary"));
+
+    let policy = AccessPolicy::from_config(&AccessConfig {
+        spreadsheet_ids: vec!["sheet-1".to_string()],
+        document_ids: vec!["doc-1".to_string()],
+        folder_ids: vec!["folder-1".to_string()],
+        calendar_ids: vec!["team@example.com".to_string()],
+    });
+    assert!(policy.restricts_files());
+    assert!(policy.file_listed("sheet-1") && policy.file_listed("doc-1") && policy.file_listed("folder-1"));
+    assert!(!policy.file_listed("sheet-2"));
+    assert!(policy.folder_allowed("folder-1") && !policy.folder_allowed("sheet-1"));
+    policy.grant("sheet-2");
+    assert!(policy.file_listed("sheet-2"));
+    assert!(policy.calendar_allowed("team@example.com"));
+    assert!(!policy.calendar_allowed("primary"));
+}
+
+#[test]
+fn test_sharing_guardrails() {
+    use gsheets_mcp::config::SharingConfig;
+    use gsheets_mcp::sharing::{check_grant, validate_max_role};
+    let open = SharingConfig::default();
+    assert!(check_grant(&open, "a@anywhere.org", "writer", "user").is_ok());
+    assert!(check_grant(&open, "a@anywhere.org", "owner", "user").is_err());
+    assert!(check_grant(&open, "", "reader", "anyone").is_err());
+    assert!(check_grant(&open, "example.com", "reader", "domain").is_err());
+    assert!(check_grant(&open, "not-an-email", "reader", "user").is_err());
+    assert!(check_grant(&open, "a@example.com", "admin", "user").is_err());
+
+    let config = SharingConfig {
+        allowed_domains: vec!["example.com".to_string()],
+        max_role: Some("commenter".to_string()),
+        allow_anyone_sharing: true,
+        allow_domain_sharing: true,
+        audit_log: None,
+    };
+    assert!(check_grant(&config, "a@Example.COM", "commenter", "user").is_ok());
+    assert!(check_grant(&config, "a@example.com", "writer", "user").is_err());
+    assert!(check_grant(&config, "team@other.com", "reader", "group").is_err());
+    assert!(check_grant(&config, "example.com", "reader", "domain").is_ok());
+    assert!(check_grant(&config, "other.com", "reader", "domain").is_err());
+    assert!(check_grant(&config, "", "reader", "anyone").is_ok());
+    assert!(validate_max_role(&config).is_ok());
+    assert!(validate_max_role(&SharingConfig { max_role: Some("admin".to_string()), ..Default::default() }).is_err());
+    assert!(validate_max_role(&SharingConfig { max_role: Some("owner".to_string()), ..Default::default() }).is_err());
+
+    let entry = gsheets_mcp::sharing::AuditEntry {
+        timestamp: 0,
+        action: "add_permission",
+        resource_id: "file-1",
+        email: Some("a@example.com"),
+        role: Some("reader"),
+        type_: Some("user"),
+        permission_id: None,
+        outcome: "ok".to_string(),
+    };
+    let json = serde_json::to_value(&entry).unwrap();
+    assert_eq!(json["type"], "user");
+    assert!(json.get("type_").is_none());
+}